@@ -0,0 +1,49 @@
+#!/bin/bash
+# Measures the code each way to die adds per call site, from a release build of the call_sites
+# example, and fails if any of them grows more than SLACK bytes past its baseline below.
+#
+# Only the code of the sites themselves is counted, not die's shared code. The baselines were
+# measured on x86_64 linux.
+set -eo pipefail
+
+SLACK=${SLACK:-16}
+
+declare -A baseline=(
+    [die_code]=12
+    [die_context]=83
+    [die_err]=83
+    [die_if]=12
+    [die_msg]=12
+    [macro_code_err]=29
+    [macro_err]=25
+    [macro_fmt]=72
+)
+
+cd "$(dirname "$0")/.."
+cargo build --release --example call_sites
+
+declare -A size sites
+while read -r _ hex _ symbol; do
+    if [[ $symbol =~ ^call_sites::([a-z_]+)::s[0-9]+$ ]]; then
+        form=${BASH_REMATCH[1]}
+        size[$form]=$(( ${size[$form]:-0} + 16#$hex ))
+        sites[$form]=$(( ${sites[$form]:-0} + 1 ))
+    fi
+done < <(nm -C -S "${CARGO_TARGET_DIR:-target}/release/examples/call_sites")
+
+if [ ${#size[@]} -eq 0 ]
+then
+    echo 'no call sites found, was the example built with symbols?'
+    exit 1
+fi
+
+failed=0
+for form in $(printf '%s\n' "${!size[@]}" | sort); do
+    per=$(( ${size[$form]} / ${sites[$form]} ))
+    printf '%-16s %4d bytes per site, %4s baseline\n' "$form" "$per" "${baseline[$form]:-?}"
+    if [ $per -gt $(( ${baseline[$form]:-0} + SLACK )) ]
+    then
+        failed=1
+    fi
+done
+exit $failed
@@ -0,0 +1,242 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Derive macros for [die]'s `DieCode` and `IntoExitCode` traits, use them through die's `derive`
+//! feature:
+//!
+//! ```toml
+//! [dependencies]
+//! die = { version = "0.2", features = ["derive"] }
+//! ```
+//!
+//! [die]: https://docs.rs/die
+
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, LitStr, Path};
+
+/// Derives `die::DieCode`, mapping each variant of an error enum to an exit code and message
+///
+/// Every variant can be annotated with `#[die(code = ..., msg = "...")]`, both are optional.
+/// `code` is any expression implementing `die::IntoExitCode`, like `65` or
+/// `die::codes::EX_DATAERR`. Without `msg` the type's own `Display` impl is used as the message.
+/// A `#[die(...)]` on the type itself sets the defaults for variants without one; `code` defaults
+/// to `die::default_exit_code()`. Structs only take the attribute on the type.
+///
+/// `#[die(crate = "path::to::die")]` on the type sets the path the generated code uses for die,
+/// for when it is renamed in `Cargo.toml` or reached through a re-export.
+///
+/// # Examples
+///
+/// ```should_panic
+/// # extern crate die;
+/// use die::{codes, DieCode, DieResult};
+///
+/// #[derive(Debug, DieCode)]
+/// #[die(code = codes::EX_SOFTWARE)]
+/// enum AppError {
+///     #[die(code = codes::EX_USAGE, msg = "unknown flag, see --help")]
+///     BadFlag,
+///     #[die(code = codes::EX_NOINPUT, msg = "input file not found")]
+///     NoInput(String),
+///     #[die(msg = "internal error")]
+///     Internal { details: String },
+/// }
+///
+/// fn parse() -> Result<(), AppError> {
+///     Err(AppError::NoInput("in.txt".to_string()))
+/// }
+///
+/// parse().die_auto(); // prints `input file not found` to stderr then exits with code 66
+/// ```
+#[proc_macro_derive(DieCode, attributes(die))]
+pub fn derive_die_code(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `die::IntoExitCode` for a fieldless enum, using each variant's discriminant as its exit
+/// code, so typed exit codes work in `die!` and the `Die` methods without casts
+///
+/// Give it a `#[repr(i32)]` and explicit discriminants, and derive `Clone` and `Copy` too to use
+/// it in the code position of `die!`. `#[die(crate = "path::to::die")]` works like for
+/// `DieCode`.
+///
+/// # Examples
+///
+/// ```should_panic
+/// # extern crate die;
+/// use die::{die, IntoExitCode};
+///
+/// #[derive(Clone, Copy, IntoExitCode)]
+/// #[repr(i32)]
+/// enum Exit {
+///     Usage = 64,
+///     ConfigMissing = 78,
+/// }
+///
+/// die!(Exit::ConfigMissing; "no config found"); // prints `no config found` to stderr then exits with code 78
+/// ```
+#[proc_macro_derive(IntoExitCode, attributes(die))]
+pub fn derive_into_exit_code(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_into_exit_code(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct DieAttr {
+    code: Option<Expr>,
+    msg: Option<LitStr>,
+    krate: Option<Path>,
+}
+
+impl DieAttr {
+    fn parse(attrs: &[Attribute]) -> syn::Result<DieAttr> {
+        let mut die = DieAttr::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("die")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("code") {
+                    die.code = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("msg") {
+                    die.msg = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("crate") {
+                    die.krate = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `code`, `msg` or `crate`"))
+                }
+            })?;
+        }
+        Ok(die)
+    }
+
+    /// Fills in what this attribute doesn't set from `defaults`
+    fn or(self, defaults: &DieAttr) -> DieAttr {
+        DieAttr {
+            code: self.code.or_else(|| defaults.code.clone()),
+            msg: self.msg.or_else(|| defaults.msg.clone()),
+            krate: self.krate.or_else(|| defaults.krate.clone()),
+        }
+    }
+
+    /// The path to die, `::die` unless set with `crate = "..."`
+    fn krate(&self) -> TokenStream2 {
+        match self.krate {
+            Some(ref krate) => quote!(#krate),
+            None => quote!(::die),
+        }
+    }
+
+    fn code(&self) -> TokenStream2 {
+        let die = self.krate();
+        match self.code {
+            Some(ref code) => quote!(#die::IntoExitCode::into_exit_code(#code)),
+            None => quote!(#die::default_exit_code()),
+        }
+    }
+
+    fn message(&self) -> TokenStream2 {
+        let die = self.krate();
+        match self.msg {
+            Some(ref msg) => quote!(f.write_str(#msg)),
+            None => quote!(#die::__private::fmt::Display::fmt(self, f)),
+        }
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let defaults = DieAttr::parse(&input.attrs)?;
+
+    let (code, message) = match input.data {
+        Data::Enum(ref data) => {
+            let mut codes = Vec::new();
+            let mut messages = Vec::new();
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let die = DieAttr::parse(&variant.attrs)?.or(&defaults);
+                let (code, message) = (die.code(), die.message());
+                codes.push(quote!(#name::#ident { .. } => #code,));
+                messages.push(quote!(#name::#ident { .. } => #message,));
+            }
+            (
+                quote!(match *self { #(#codes)* }),
+                quote!(match *self { #(#messages)* }),
+            )
+        }
+        Data::Struct(_) => (defaults.code(), defaults.message()),
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                input,
+                "DieCode can only be derived for enums and structs",
+            ))
+        }
+    };
+
+    let die = defaults.krate();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #die::DieCode for #name #ty_generics #where_clause {
+            fn exit_code(&self) -> i32 {
+                #code
+            }
+
+            fn die_message(&self, f: &mut #die::__private::fmt::Formatter) -> #die::__private::fmt::Result {
+                #message
+            }
+        }
+    })
+}
+
+fn expand_into_exit_code(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let die = DieAttr::parse(&input.attrs)?;
+    if die.code.is_some() || die.msg.is_some() {
+        return Err(Error::new_spanned(
+            input,
+            "IntoExitCode only takes `#[die(crate = ...)]`, the codes are the discriminants",
+        ));
+    }
+    match input.data {
+        Data::Enum(ref data) => {
+            if let Some(variant) = data.variants.iter().find(|v| !v.fields.is_empty()) {
+                return Err(Error::new_spanned(
+                    variant,
+                    "IntoExitCode can only be derived for enums without fields",
+                ));
+            }
+        }
+        _ => {
+            return Err(Error::new_spanned(
+                input,
+                "IntoExitCode can only be derived for enums",
+            ))
+        }
+    }
+
+    let die = die.krate();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #die::IntoExitCode for #name #ty_generics #where_clause {
+            #[inline]
+            fn into_exit_code(self) -> i32 {
+                self as i32
+            }
+        }
+    })
+}
@@ -0,0 +1,100 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Call sites of the common ways to die, for `.ci/size.sh` to measure the code each one adds to a
+//! program
+//!
+//! Every site is its own function, `call_sites::<form>::s<n>`, which is never inlined, so the
+//! size of its symbol in a release build is the cost of that site, without die's shared code.
+//! Each site compares against its own constant so the compiler can't fold them together. None
+//! of them die when run, unless given over a hundred arguments.
+
+#[macro_use]
+extern crate die;
+
+use std::error::Error;
+use std::fmt;
+use std::hint::black_box;
+
+#[derive(Debug)]
+struct E(u32);
+
+impl fmt::Display for E {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "error {}", self.0)
+    }
+}
+
+impl Error for E {}
+
+macro_rules! sites {
+    ($($form:ident => |$r:ident| $body:block)*) => {$(
+        mod $form {
+            #[allow(unused_imports)]
+            use die::{Die, DieResult};
+            use super::E;
+
+            sites!(@sites |$r| $body
+                s0 = 100, s1 = 101, s2 = 102, s3 = 103, s4 = 104, s5 = 105, s6 = 106, s7 = 107,
+                s8 = 108, s9 = 109, s10 = 110, s11 = 111, s12 = 112, s13 = 113, s14 = 114, s15 = 115);
+        }
+    )*
+
+        fn all(x: u32) {
+            $($form::all(x);)*
+        }
+    };
+    (@sites |$r:ident| $body:block $($site:ident = $n:expr),*) => {
+        $(
+            #[inline(never)]
+            pub fn $site(x: u32) {
+                let $r: Result<u32, E> = if x == $n { Err(E(x)) } else { Ok(x) };
+                $body
+            }
+        )*
+
+        pub fn all(x: u32) {
+            $($site(x);)*
+        }
+    };
+}
+
+sites! {
+    die_msg => |r| {
+        let _: u32 = r.die("failed");
+    }
+    die_code => |r| {
+        let _: u32 = r.die_code("failed", 3);
+    }
+    die_err => |r| {
+        let _: u32 = r.die_err("failed");
+    }
+    die_context => |r| {
+        let _: u32 = r.die_context("failed");
+    }
+    macro_fmt => |r| {
+        if let Err(e) = r {
+            die!("failed: {}", e);
+        }
+    }
+    macro_err => |r| {
+        if let Err(e) = r {
+            die!(e);
+        }
+    }
+    macro_code_err => |r| {
+        if let Err(e) = r {
+            die!(e; 3);
+        }
+    }
+    die_if => |r| {
+        die_if!(r.is_err(), "failed");
+    }
+}
+
+fn main() {
+    all(black_box(std::env::args().count() as u32));
+}
@@ -0,0 +1,168 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Not public API, used by the macros
+//!
+//! `PrintExit` can't have a blanket impl for every [`Error`] next to its impls for `&str` and
+//! `String`, so [`die`]!() picks one with autoref: `(&Wrap(&x)).die_kind()` finds
+//! [`PrintExitKind`] first, taking `&Wrap` by value, and only falls back to the [`ErrorKind`]
+//! traits, which need another autoref, when `x` doesn't implement `PrintExit`.
+//!
+//! Nor can it have blanket impls for `(code, impl Display)` in both orders, they overlap with each
+//! other and the error ones, so `die!(code; x)` goes one level further:
+//! `(&&Wrap(&(code, x))).die_kind()` finds [`PrintExitKind`] on `&Wrap` first, then the error
+//! kinds on `&&Wrap`, then, after a deref, the [`CodeDisplayKind`] and [`DisplayCodeKind`] ones
+//! on `Wrap`, so any [`Display`] is a message, like a `Cow` or a user type.
+//!
+//! [`Error`]: https://doc.rust-lang.org/std/error/trait.Error.html
+//! [`die`]: ../macro.die.html
+//! [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+
+use core::error::Error;
+
+use exit_code::DefaultExitCode;
+#[cfg(feature = "std")]
+use std::panic::Location;
+use {print_exit, print_exit_chain, IntoExitCode, PrintExit};
+#[cfg(feature = "std")]
+use {usage, warn};
+
+pub use core::fmt;
+// for the macros, so they work without the prelude too
+pub use core::option::Option;
+pub use core::{concat, format_args, stringify};
+#[cfg(feature = "std")]
+pub use std::boxed::Box;
+
+#[cfg(feature = "std")]
+pub use abort::to_abort;
+#[cfg(feature = "std")]
+pub use output::to_stdout;
+#[cfg(feature = "std")]
+pub use payload::set_payload;
+#[cfg(all(feature = "signal", unix))]
+pub use signal::to_signal;
+
+#[cfg(feature = "std")]
+#[inline]
+#[track_caller]
+pub fn die_usage(message: Option<&dyn fmt::Display>) -> ! {
+    usage::die_usage(message)
+}
+
+#[cfg(feature = "std")]
+#[inline]
+#[track_caller]
+pub fn warn(message: &dyn fmt::Display) {
+    warn::print(message, Location::caller())
+}
+
+pub struct Wrap<'a, T: ?Sized + 'a>(pub &'a T);
+
+pub trait PrintExitKind {
+    fn die_kind(&self) -> !;
+}
+
+impl<T: PrintExit + ?Sized> PrintExitKind for Wrap<'_, T> {
+    #[inline]
+    #[track_caller]
+    fn die_kind(&self) -> ! {
+        self.0.print_exit()
+    }
+}
+
+impl<T: PrintExit + ?Sized> PrintExitKind for &Wrap<'_, T> {
+    #[inline]
+    #[track_caller]
+    fn die_kind(&self) -> ! {
+        self.0.print_exit()
+    }
+}
+
+pub trait ErrorKind {
+    fn die_kind(&self) -> !;
+}
+
+impl<E: Error> ErrorKind for &Wrap<'_, E> {
+    #[inline]
+    #[track_caller]
+    fn die_kind(&self) -> ! {
+        print_exit_chain(DefaultExitCode, self.0)
+    }
+}
+
+pub trait CodeErrorKind {
+    fn die_kind(&self) -> !;
+}
+
+impl<C: IntoExitCode + Copy, E: Error> CodeErrorKind for &&Wrap<'_, (C, E)> {
+    #[inline]
+    #[track_caller]
+    fn die_kind(&self) -> ! {
+        let (code, ref err) = *self.0;
+        print_exit_chain(code.into_exit_code(), err)
+    }
+}
+
+pub trait ErrorCodeKind {
+    fn die_kind(&self) -> !;
+}
+
+impl<E: Error, C: IntoExitCode + Copy> ErrorCodeKind for &&Wrap<'_, (E, C)> {
+    #[inline]
+    #[track_caller]
+    fn die_kind(&self) -> ! {
+        let (ref err, code) = *self.0;
+        print_exit_chain(code.into_exit_code(), err)
+    }
+}
+
+pub trait CodeDisplayKind {
+    fn die_kind(&self) -> !;
+}
+
+impl<C: IntoExitCode + Copy, D: fmt::Display> CodeDisplayKind for Wrap<'_, (C, D)> {
+    #[inline]
+    #[track_caller]
+    fn die_kind(&self) -> ! {
+        let (code, ref msg) = *self.0;
+        print_exit(code, msg)
+    }
+}
+
+pub trait DisplayCodeKind {
+    fn die_kind(&self) -> !;
+}
+
+impl<D: fmt::Display, C: IntoExitCode + Copy> DisplayCodeKind for Wrap<'_, (D, C)> {
+    #[inline]
+    #[track_caller]
+    fn die_kind(&self) -> ! {
+        let (ref msg, code) = *self.0;
+        print_exit(code, msg)
+    }
+}
+
+/// The success value of an [`Option`] or [`Result`], for [`or_die`]!()
+///
+/// [`or_die`]: ../macro.or_die.html
+pub trait OrDie<T> {
+    fn or_die(self) -> Option<T>;
+}
+
+impl<T> OrDie<T> for Option<T> {
+    #[inline]
+    fn or_die(self) -> Option<T> {
+        self
+    }
+}
+
+impl<T, E> OrDie<T> for Result<T, E> {
+    #[inline]
+    fn or_die(self) -> Option<T> {
+        self.ok()
+    }
+}
@@ -0,0 +1,21 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::cell::Cell;
+
+thread_local! {
+    static ABORT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Makes the next die on this thread abort instead of exiting, for die_abort!()
+pub fn to_abort() {
+    ABORT.with(|abort| abort.set(true));
+}
+
+/// Whether the die in progress on this thread aborts, resetting it for the next one
+pub(crate) fn take_abort() -> bool {
+    ABORT.with(|abort| abort.replace(false))
+}
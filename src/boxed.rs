@@ -0,0 +1,127 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::error::Error;
+use std::fmt;
+
+use exit_code::DefaultExitCode;
+use {broken_pipe, print_exit_chain, print_exit_context, IntoExitCode, PrintExit};
+
+/// `DieBoxed` is a trait implemented on [`Result`]s holding a boxed [`Error`], the
+/// `Result<T, Box<dyn Error>>` of many a `main`, to print the error and all of its sources after
+/// the message instead of discarding them like [`Die`] does
+///
+/// Boxed errors don't implement [`Error`] themselves, so the [`DieResult`] methods can only print
+/// their own message, and [`die`]!(err) without this wouldn't accept them at all.
+///
+/// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+/// [`Error`]: https://doc.rust-lang.org/std/error/trait.Error.html
+/// [`Die`]: trait.Die.html
+/// [`DieResult`]: trait.DieResult.html
+/// [`die`]: macro.die.html
+pub trait DieBoxed<T> {
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(1)][exit] if the value is an [`Err`], after printing the passed
+    /// message followed by `: `, the error, and each of its [`source`]s on an indented
+    /// `caused by:` line to [`stderr`].
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use die::DieBoxed;
+    /// # use std::error::Error;
+    /// let x: Result<u32, Box<dyn Error>> = Err("emergency failure".into());
+    /// x.die_chain("failed"); // prints `failed: emergency failure` to stderr then exits with code 1
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`source`]: https://doc.rust-lang.org/std/error/trait.Error.html#method.source
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    fn die_chain(self, msg: impl fmt::Display) -> T;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the value is an [`Err`], after printing the
+    /// passed message followed by `: `, the error, and each of its [`source`]s on an indented
+    /// `caused by:` line to [`stderr`].
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use die::DieBoxed;
+    /// # use std::error::Error;
+    /// let x: Result<u32, Box<dyn Error + Send + Sync>> = Err("emergency failure".into());
+    /// x.die_chain_code("failed", 3); // prints `failed: emergency failure` to stderr then exits with code 3
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`source`]: https://doc.rust-lang.org/std/error/trait.Error.html#method.source
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    fn die_chain_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T;
+}
+
+macro_rules! impl_boxed {
+    ($($err:ty),+) => {$(
+        impl<T> DieBoxed<T> for Result<T, Box<$err>> {
+            #[inline]
+            #[track_caller]
+            fn die_chain(self, msg: impl fmt::Display) -> T {
+                self.die_chain_code(msg, DefaultExitCode)
+            }
+            #[inline]
+            #[track_caller]
+            fn die_chain_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T {
+                match self {
+                    Ok(t) => t,
+                    Err(err) => {
+                        broken_pipe::check(&*err);
+                        print_exit_context(exit_code.into_exit_code(), &msg, &*err)
+                    }
+                }
+            }
+        }
+
+        impl PrintExit for Box<$err> {
+            #[inline]
+            #[track_caller]
+            fn print_exit(&self) -> ! {
+                broken_pipe::check(&**self);
+                print_exit_chain(DefaultExitCode, &**self)
+            }
+        }
+
+        impl<C: IntoExitCode + Copy> PrintExit for (C, Box<$err>) {
+            #[inline]
+            #[track_caller]
+            fn print_exit(&self) -> ! {
+                broken_pipe::check(&*self.1);
+                print_exit_chain(self.0.into_exit_code(), &*self.1)
+            }
+        }
+
+        impl<C: IntoExitCode + Copy> PrintExit for (Box<$err>, C) {
+            #[inline]
+            #[track_caller]
+            fn print_exit(&self) -> ! {
+                broken_pipe::check(&*self.0);
+                print_exit_chain(self.1.into_exit_code(), &*self.0)
+            }
+        }
+    )+};
+}
+
+impl_boxed!(dyn Error, dyn Error + Send, dyn Error + Send + Sync);
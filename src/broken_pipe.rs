@@ -0,0 +1,104 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::error::Error;
+use std::io;
+use std::iter;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+use exit_code::DefaultExitCode;
+use {print_exit_chain, IntoExitCode, PrintExit};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CODE: AtomicI32 = AtomicI32::new(0);
+
+/// Makes dying on an [`io::Error`] of kind [`BrokenPipe`] exit quietly with `code`, usually `0`,
+/// like well-behaved filters piped into `head` do
+///
+/// This applies to errors passed to [`die`]!(), returned from [`run`] and anywhere in the source
+/// chain of those, including inside an `anyhow::Error` or `eyre::Report`. The hook and
+/// integrations still run, without a message.
+///
+/// [`io::Error`]: https://doc.rust-lang.org/std/io/struct.Error.html
+/// [`BrokenPipe`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.BrokenPipe
+/// [`die`]: macro.die.html
+/// [`run`]: fn.run.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// use std::io::{Error, ErrorKind};
+/// die::set_broken_pipe(141);
+/// let err = Error::from(ErrorKind::BrokenPipe);
+/// die!(err); // prints nothing, only exits with code 141
+/// ```
+pub fn set_broken_pipe(code: impl IntoExitCode) {
+    CODE.store(code.into_exit_code(), Ordering::Relaxed);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// The code to quietly exit with if `err`, or one of its sources, is a broken pipe and that is
+/// enabled
+pub(crate) fn code(err: &(dyn Error + 'static)) -> Option<i32> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    let mut chain = iter::successors(Some(err), |&err| err.source());
+    if chain.any(|err| {
+        err.downcast_ref::<io::Error>()
+            .is_some_and(|err| err.kind() == io::ErrorKind::BrokenPipe)
+    }) {
+        Some(CODE.load(Ordering::Relaxed))
+    } else {
+        None
+    }
+}
+
+/// Exits quietly if `err` is a broken pipe and that is enabled, returns otherwise
+#[inline]
+#[track_caller]
+pub(crate) fn check(err: &(dyn Error + 'static)) {
+    if let Some(code) = code(err) {
+        code.print_exit()
+    }
+}
+
+/// Like [`check`], for errors that may not be `'static`, so only their sources can be checked
+#[inline]
+#[track_caller]
+pub(crate) fn check_sources(err: &dyn Error) {
+    if let Some(source) = err.source() {
+        check(source)
+    }
+}
+
+impl PrintExit for io::Error {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        check(self);
+        print_exit_chain(DefaultExitCode, self)
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (C, io::Error) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        check(&self.1);
+        print_exit_chain(self.0.into_exit_code(), &self.1)
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (io::Error, C) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        check(&self.0);
+        print_exit_chain(self.1.into_exit_code(), &self.0)
+    }
+}
@@ -0,0 +1,32 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::error::Error;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use config;
+
+/// Displays an error followed by each of its [`source`]s on an indented `caused by:` line
+///
+/// [`source`]: https://doc.rust-lang.org/std/error/trait.Error.html#method.source
+pub(crate) struct Chain<'a>(pub(crate) &'a (dyn Error + 'a));
+
+impl fmt::Display for Chain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#}", self.0)?;
+        #[cfg(feature = "std")]
+        if config::verbosity() == 0 {
+            return Ok(());
+        }
+        let mut source = self.0.source();
+        while let Some(cause) = source {
+            write!(f, "\n    caused by: {:#}", cause)?;
+            source = cause.source();
+        }
+        Ok(())
+    }
+}
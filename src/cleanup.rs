@@ -0,0 +1,82 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+
+type Cleanup = Box<dyn FnOnce() + Send>;
+
+static CLEANUPS: Mutex<Vec<Cleanup>> = Mutex::new(Vec::new());
+
+/// Registers `f` to run right before the process dies, like `atexit`
+///
+/// [`process::exit`] skips destructors, so cleanup like removing a pidfile or releasing a lock
+/// file goes here instead. Cleanups run after the die hook, in reverse order of registration,
+/// each only once.
+///
+/// [`process::exit`]: https://doc.rust-lang.org/std/process/fn.exit.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// std::fs::write("/tmp/die-example.pid", "42").unwrap();
+/// die::on_exit(|| drop(std::fs::remove_file("/tmp/die-example.pid")));
+/// die!("shutting down"); // prints `shutting down`, removes the pidfile, then exits with code 1
+/// ```
+pub fn on_exit(f: impl FnOnce() + Send + 'static) {
+    CLEANUPS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Box::new(f));
+}
+
+/// Registers a temp file or directory to be removed right before the process dies, as
+/// [`process::exit`] skips the `Drop` of guards like `tempfile`'s, which then leak it
+///
+/// A directory is removed with everything in it, a symlink is removed itself and not what it
+/// points to. It runs like an [`on_exit`] cleanup, so in the same reverse order of registration,
+/// and removing a path that is already gone, or fails to be removed, is ignored. Nothing happens
+/// if the program ends without dying, when the guards do their job.
+///
+/// [`process::exit`]: https://doc.rust-lang.org/std/process/fn.exit.html
+/// [`on_exit`]: fn.on_exit.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// let dir = std::env::temp_dir().join("die-example-work");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// die::remove_on_exit(&dir);
+/// die!("download failed"); // prints `download failed`, removes the directory, then exits with code 1
+/// ```
+pub fn remove_on_exit(path: impl Into<PathBuf>) {
+    let path = path.into();
+    on_exit(move || {
+        let _ = match fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(&path),
+            Ok(_) => fs::remove_file(&path),
+            Err(_) => return,
+        };
+    });
+}
+
+/// Runs the registered cleanups, last registered first
+pub(crate) fn run() {
+    loop {
+        // don't hold the lock while running a cleanup, it may register another or die itself
+        let cleanup = CLEANUPS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop();
+        match cleanup {
+            Some(cleanup) => cleanup(),
+            None => return,
+        }
+    }
+}
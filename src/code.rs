@@ -0,0 +1,103 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(feature = "std")]
+use std::process;
+
+use IntoExitCode;
+
+/// An exit code that can't accidentally be `0` on an error path
+///
+/// A computed `0` passed to [`die`]!() makes a fatal failure look like success to shells and
+/// scripts. The only way to get a `0` here is [`SUCCESS`], [`failure`] rejects it, and the named
+/// constants are the [`codes`] from `sysexits.h`.
+///
+/// [`die`]: macro.die.html
+/// [`SUCCESS`]: #associatedconstant.SUCCESS
+/// [`failure`]: #method.failure
+/// [`codes`]: codes/index.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::{die, ExitCode};
+/// let failed = 3;
+/// let code = ExitCode::failure(failed).unwrap_or(ExitCode::SOFTWARE);
+/// die!(code; "{} checks failed", failed); // prints `3 checks failed` to stderr then exits with code 3
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExitCode(u8);
+
+impl ExitCode {
+    /// Success, `0`
+    pub const SUCCESS: ExitCode = ExitCode(0);
+    /// Generic failure, `1`
+    pub const FAILURE: ExitCode = ExitCode(1);
+    /// The command was used incorrectly, like wrong arguments, `64`
+    pub const USAGE: ExitCode = ExitCode(64);
+    /// The input data was incorrect, `65`
+    pub const DATAERR: ExitCode = ExitCode(65);
+    /// An input file did not exist or was not readable, `66`
+    pub const NOINPUT: ExitCode = ExitCode(66);
+    /// The user specified did not exist, `67`
+    pub const NOUSER: ExitCode = ExitCode(67);
+    /// The host specified did not exist, `68`
+    pub const NOHOST: ExitCode = ExitCode(68);
+    /// A service is unavailable, `69`
+    pub const UNAVAILABLE: ExitCode = ExitCode(69);
+    /// An internal software error was detected, `70`
+    pub const SOFTWARE: ExitCode = ExitCode(70);
+    /// An operating system error was detected, `71`
+    pub const OSERR: ExitCode = ExitCode(71);
+    /// A system file did not exist or was not readable, `72`
+    pub const OSFILE: ExitCode = ExitCode(72);
+    /// A user specified output file could not be created, `73`
+    pub const CANTCREAT: ExitCode = ExitCode(73);
+    /// An error occurred while doing I/O on some file, `74`
+    pub const IOERR: ExitCode = ExitCode(74);
+    /// A temporary failure, the user is invited to retry, `75`
+    pub const TEMPFAIL: ExitCode = ExitCode(75);
+    /// The remote system returned something invalid during a protocol exchange, `76`
+    pub const PROTOCOL: ExitCode = ExitCode(76);
+    /// Insufficient permission to perform the operation, `77`
+    pub const NOPERM: ExitCode = ExitCode(77);
+    /// Something was found unconfigured or misconfigured, `78`
+    pub const CONFIG: ExitCode = ExitCode(78);
+
+    /// A failure exit code, or `None` for `0`, which would be success
+    pub const fn failure(code: u8) -> Option<ExitCode> {
+        match code {
+            0 => None,
+            code => Some(ExitCode(code)),
+        }
+    }
+
+    /// The code as a `u8`
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    /// Whether this is [`SUCCESS`]
+    ///
+    /// [`SUCCESS`]: #associatedconstant.SUCCESS
+    pub const fn is_success(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl IntoExitCode for ExitCode {
+    #[inline]
+    fn into_exit_code(self) -> i32 {
+        i32::from(self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ExitCode> for process::ExitCode {
+    fn from(code: ExitCode) -> process::ExitCode {
+        process::ExitCode::from(code.0)
+    }
+}
@@ -0,0 +1,103 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Exit code constants from BSD's [`sysexits.h`], usable anywhere an exit code is accepted
+//!
+//! ```should_panic
+//! # use die::die;
+//! use die::codes;
+//! die!(codes::EX_USAGE; "unknown flag -q"); // prints `unknown flag -q` to stderr then exits with code 64
+//! ```
+//!
+//! [`sysexits.h`]: https://man.freebsd.org/cgi/man.cgi?query=sysexits
+
+/// Successful termination
+pub const EX_OK: i32 = 0;
+/// The command was used incorrectly, e.g. with the wrong number of arguments, a bad flag, or a
+/// bad syntax in a parameter
+pub const EX_USAGE: i32 = 64;
+/// The input data was incorrect in some way, only for user data, not system files
+pub const EX_DATAERR: i32 = 65;
+/// An input file (not a system file) did not exist or was not readable
+pub const EX_NOINPUT: i32 = 66;
+/// The user specified did not exist
+pub const EX_NOUSER: i32 = 67;
+/// The host specified did not exist
+pub const EX_NOHOST: i32 = 68;
+/// A service is unavailable, e.g. a support program or file does not exist; a catchall when
+/// something doesn't work and the reason is unknown
+pub const EX_UNAVAILABLE: i32 = 69;
+/// An internal software error has been detected, limited to non-operating system related errors
+pub const EX_SOFTWARE: i32 = 70;
+/// An operating system error has been detected, such as "cannot fork" or "cannot create pipe"
+pub const EX_OSERR: i32 = 71;
+/// Some system file (e.g. `/etc/passwd`) does not exist, cannot be opened, or has an error
+pub const EX_OSFILE: i32 = 72;
+/// A (user specified) output file cannot be created
+pub const EX_CANTCREAT: i32 = 73;
+/// An error occurred while doing I/O on some file
+pub const EX_IOERR: i32 = 74;
+/// Temporary failure, indicating something that is not really an error, the user is invited to
+/// retry later
+pub const EX_TEMPFAIL: i32 = 75;
+/// The remote system returned something that was "not possible" during a protocol exchange
+pub const EX_PROTOCOL: i32 = 76;
+/// The user did not have sufficient permission to perform the operation, not for file system
+/// problems, which should use [`EX_NOINPUT`] or [`EX_CANTCREAT`]
+///
+/// [`EX_NOINPUT`]: constant.EX_NOINPUT.html
+/// [`EX_CANTCREAT`]: constant.EX_CANTCREAT.html
+pub const EX_NOPERM: i32 = 77;
+/// Something was found in an unconfigured or misconfigured state
+pub const EX_CONFIG: i32 = 78;
+
+/// The sysexits code for a kind of I/O error, used by [`DieIo::die_io`]
+///
+/// | [`ErrorKind`]                                                     | code               |
+/// |-------------------------------------------------------------------|--------------------|
+/// | `NotFound`                                                        | [`EX_NOINPUT`]     |
+/// | `PermissionDenied`                                                | [`EX_NOPERM`]      |
+/// | `AlreadyExists`                                                   | [`EX_CANTCREAT`]   |
+/// | `InvalidInput`, `InvalidData`, `UnexpectedEof`                    | [`EX_DATAERR`]     |
+/// | `ConnectionRefused`, `ConnectionReset`, `ConnectionAborted`        | [`EX_UNAVAILABLE`] |
+/// | `NotConnected`, `AddrInUse`, `AddrNotAvailable`, `Unsupported`    | [`EX_UNAVAILABLE`] |
+/// | `TimedOut`, `Interrupted`, `WouldBlock`                           | [`EX_TEMPFAIL`]    |
+/// | `OutOfMemory`                                                     | [`EX_OSERR`]       |
+/// | anything else                                                     | [`EX_IOERR`]       |
+///
+/// # Examples
+///
+/// ```
+/// use die::codes;
+/// use std::io::ErrorKind;
+/// assert_eq!(codes::from_io_error_kind(ErrorKind::NotFound), codes::EX_NOINPUT);
+/// ```
+///
+/// [`DieIo::die_io`]: ../trait.DieIo.html#tymethod.die_io
+/// [`ErrorKind`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html
+/// [`EX_NOINPUT`]: constant.EX_NOINPUT.html
+/// [`EX_NOPERM`]: constant.EX_NOPERM.html
+/// [`EX_CANTCREAT`]: constant.EX_CANTCREAT.html
+/// [`EX_DATAERR`]: constant.EX_DATAERR.html
+/// [`EX_UNAVAILABLE`]: constant.EX_UNAVAILABLE.html
+/// [`EX_TEMPFAIL`]: constant.EX_TEMPFAIL.html
+/// [`EX_OSERR`]: constant.EX_OSERR.html
+/// [`EX_IOERR`]: constant.EX_IOERR.html
+#[cfg(feature = "std")]
+pub fn from_io_error_kind(kind: ::std::io::ErrorKind) -> i32 {
+    use std::io::ErrorKind::*;
+    match kind {
+        NotFound => EX_NOINPUT,
+        PermissionDenied => EX_NOPERM,
+        AlreadyExists => EX_CANTCREAT,
+        InvalidInput | InvalidData | UnexpectedEof => EX_DATAERR,
+        ConnectionRefused | ConnectionReset | ConnectionAborted | NotConnected | AddrInUse
+        | AddrNotAvailable | Unsupported => EX_UNAVAILABLE,
+        TimedOut | Interrupted | WouldBlock => EX_TEMPFAIL,
+        OutOfMemory => EX_OSERR,
+        _ => EX_IOERR,
+    }
+}
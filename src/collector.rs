@@ -0,0 +1,111 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::mem;
+use std::panic::Location;
+
+use {exit, unwind, DieInfo};
+
+/// Collects non-fatal problems, then dies with all of them at once, for linters and validators
+/// that should report everything they found instead of stopping at the first one
+///
+/// [`finish`], or dropping the collector, dies if anything was pushed, printing a numbered
+/// summary and exiting with the number of problems, at most 255, as the exit code.
+///
+/// [`finish`]: #method.finish
+///
+/// # Examples
+///
+/// ```should_panic
+/// let mut problems = die::ErrorCollector::new();
+/// for line in ["name = 1", "name =", "= 2"] {
+///     if !line.contains(" = ") {
+///         problems.push(format_args!("invalid line `{}`", line));
+///     }
+/// }
+/// problems.finish(); // prints `2 errors:`, then `  1. invalid line `name =`` and `  2. invalid line `= 2`` to stderr then exits with code 2
+/// ```
+#[derive(Debug)]
+pub struct ErrorCollector {
+    errors: Vec<String>,
+    location: &'static Location<'static>,
+}
+
+impl ErrorCollector {
+    /// Creates an empty collector, the location it dies at is where it was created
+    #[track_caller]
+    pub fn new() -> ErrorCollector {
+        ErrorCollector {
+            errors: Vec::new(),
+            location: Location::caller(),
+        }
+    }
+
+    /// Records a problem
+    pub fn push(&mut self, err: impl fmt::Display) {
+        self.errors.push(err.to_string());
+    }
+
+    /// The number of problems recorded so far
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Whether no problem was recorded so far
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Dies if any problem was recorded, otherwise does nothing
+    pub fn finish(mut self) {
+        self.die();
+    }
+
+    fn die(&mut self) {
+        if self.errors.is_empty() {
+            return;
+        }
+        // taken so dropping while unwinding with the panic feature doesn't die again
+        let errors = mem::take(&mut self.errors);
+        let summary = Summary(&errors);
+        let mut info = DieInfo::new(Some(&summary), errors.len().min(255) as i32);
+        info.location = self.location;
+        exit(info)
+    }
+}
+
+impl Default for ErrorCollector {
+    #[track_caller]
+    fn default() -> ErrorCollector {
+        ErrorCollector::new()
+    }
+}
+
+impl Drop for ErrorCollector {
+    fn drop(&mut self) {
+        // unwinding out of a drop that is already unwinding would abort
+        if unwind::unwinds() && ::std::thread::panicking() {
+            return;
+        }
+        self.die();
+    }
+}
+
+struct Summary<'a>(&'a [String]);
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.len() {
+            1 => f.write_str("1 error:")?,
+            n => write!(f, "{} errors:", n)?,
+        }
+        for (i, err) in self.0.iter().enumerate() {
+            write!(f, "\n  {}. {}", i + 1, err)?;
+        }
+        Ok(())
+    }
+}
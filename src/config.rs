@@ -0,0 +1,933 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::{PoisonError, RwLock};
+use std::time::Duration;
+
+#[cfg(feature = "tokio")]
+use shutdown;
+use {crash_log, crash_report, exit_code, log_file, IntoExitCode};
+
+static COLOR: AtomicU8 = AtomicU8::new(Color::Auto as u8);
+static BACKTRACE: AtomicBool = AtomicBool::new(false);
+static LOCATION: AtomicBool = AtomicBool::new(false);
+static PERL_STYLE: AtomicBool = AtomicBool::new(false);
+static ERROR_LABEL: AtomicBool = AtomicBool::new(false);
+static PROGRAM_PREFIX: AtomicBool = AtomicBool::new(false);
+static CLAMP_EXIT_CODE: AtomicBool = AtomicBool::new(false);
+static FLUSH_STDOUT: AtomicBool = AtomicBool::new(true);
+static LOCK_STDOUT: AtomicBool = AtomicBool::new(false);
+static THREAD_NAME: AtomicBool = AtomicBool::new(false);
+static TIMESTAMP: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+static NO_EXIT: AtomicBool = AtomicBool::new(false);
+static PAUSE: AtomicBool = AtomicBool::new(false);
+static EXIT_DELAY_MS: AtomicU64 = AtomicU64::new(0);
+static VERBOSITY: AtomicU8 = AtomicU8::new(1);
+#[cfg(feature = "json")]
+static JSON: AtomicBool = AtomicBool::new(false);
+#[cfg(feature = "logfmt")]
+static LOGFMT: AtomicBool = AtomicBool::new(false);
+static PROGRAM_NAME: RwLock<Option<String>> = RwLock::new(None);
+#[cfg(feature = "logcat")]
+static LOGCAT_TAG: RwLock<Option<String>> = RwLock::new(None);
+#[cfg(feature = "eventlog")]
+static EVENT_SOURCE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Starts building a process-global configuration, applied to every later die
+///
+/// Only the settings called on the builder are changed by [`Config::apply`], the rest keep their
+/// current value. The standalone setters like [`set_location`] change one setting each.
+///
+/// [`Config::apply`]: struct.Config.html#method.apply
+/// [`set_location`]: fn.set_location.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::config()
+///     .default_exit_code(2)
+///     .program_name("myprog")
+///     .color(die::Color::Never)
+///     .location(true)
+///     .apply();
+/// die!("no such file"); // prints `myprog: no such file (at src/main.rs:9)` then exits with code 2
+/// ```
+pub fn config() -> Config {
+    Config::default()
+}
+
+/// A builder for the process-global configuration, see [`config`]
+///
+/// [`config`]: fn.config.html
+#[derive(Debug, Default, Clone)]
+#[must_use = "the configuration does nothing until applied"]
+pub struct Config {
+    default_exit_code: Option<i32>,
+    color: Option<Color>,
+    backtrace: Option<bool>,
+    location: Option<bool>,
+    perl_style: Option<bool>,
+    error_label: Option<bool>,
+    program_prefix: Option<bool>,
+    program_name: Option<String>,
+    clamp_exit_code: Option<bool>,
+    flush_stdout: Option<bool>,
+    lock_stdout: Option<bool>,
+    timestamp: Option<bool>,
+    thread_name: Option<bool>,
+    quiet: Option<bool>,
+    no_exit: Option<bool>,
+    pause: Option<bool>,
+    exit_delay: Option<Duration>,
+    verbosity: Option<u8>,
+    crash_report: Option<String>,
+    log_file: Option<PathBuf>,
+    crash_log_dir: Option<(PathBuf, usize)>,
+    #[cfg(feature = "json")]
+    json: Option<bool>,
+    #[cfg(feature = "logfmt")]
+    logfmt: Option<bool>,
+    #[cfg(feature = "logcat")]
+    logcat_tag: Option<String>,
+    #[cfg(feature = "eventlog")]
+    event_source: Option<String>,
+    #[cfg(feature = "tokio")]
+    shutdown_timeout: Option<Duration>,
+}
+
+impl Config {
+    /// Sets the exit code used when none is given, like by `die!("msg")`, [`DEFAULT_EXIT_CODE`]
+    /// by default
+    ///
+    /// [`DEFAULT_EXIT_CODE`]: constant.DEFAULT_EXIT_CODE.html
+    pub fn default_exit_code(mut self, code: impl IntoExitCode) -> Config {
+        self.default_exit_code = Some(code.into_exit_code());
+        self
+    }
+
+    /// Sets whether exit codes outside 0-255 are clamped to 255, with a warning, on unix, off by
+    /// default
+    ///
+    /// Unix only passes the low 8 bits of the exit code on to the parent, so the `1000` from
+    /// `die!(1000)` would show up as `232` in `$?`, and `256` as success. Without clamping, debug
+    /// builds still print a warning.
+    pub fn clamp_exit_code(mut self, enabled: bool) -> Config {
+        self.clamp_exit_code = Some(enabled);
+        self
+    }
+
+    /// Sets when the `error: ` label is colored, see [`Color`]
+    ///
+    /// [`Color`]: enum.Color.html
+    pub fn color(mut self, color: Color) -> Config {
+        self.color = Some(color);
+        self
+    }
+
+    /// Like [`set_backtrace`]
+    ///
+    /// [`set_backtrace`]: fn.set_backtrace.html
+    pub fn backtrace(mut self, enabled: bool) -> Config {
+        self.backtrace = Some(enabled);
+        self
+    }
+
+    /// Like [`set_location`]
+    ///
+    /// [`set_location`]: fn.set_location.html
+    pub fn location(mut self, enabled: bool) -> Config {
+        self.location = Some(enabled);
+        self
+    }
+
+    /// Like [`set_perl_style`]
+    ///
+    /// [`set_perl_style`]: fn.set_perl_style.html
+    pub fn perl_style(mut self, enabled: bool) -> Config {
+        self.perl_style = Some(enabled);
+        self
+    }
+
+    /// Like [`set_error_label`]
+    ///
+    /// [`set_error_label`]: fn.set_error_label.html
+    pub fn error_label(mut self, enabled: bool) -> Config {
+        self.error_label = Some(enabled);
+        self
+    }
+
+    /// Like [`set_program_prefix`]
+    ///
+    /// [`set_program_prefix`]: fn.set_program_prefix.html
+    pub fn program_prefix(mut self, enabled: bool) -> Config {
+        self.program_prefix = Some(enabled);
+        self
+    }
+
+    /// Like [`set_program_name`], the message prefix
+    ///
+    /// [`set_program_name`]: fn.set_program_name.html
+    pub fn program_name(mut self, name: impl Into<String>) -> Config {
+        self.program_name = Some(name.into());
+        self
+    }
+
+    /// Like [`set_flush_stdout`]
+    ///
+    /// [`set_flush_stdout`]: fn.set_flush_stdout.html
+    pub fn flush_stdout(mut self, enabled: bool) -> Config {
+        self.flush_stdout = Some(enabled);
+        self
+    }
+
+    /// Like [`set_lock_stdout`]
+    ///
+    /// [`set_lock_stdout`]: fn.set_lock_stdout.html
+    pub fn lock_stdout(mut self, enabled: bool) -> Config {
+        self.lock_stdout = Some(enabled);
+        self
+    }
+
+    /// Like [`set_timestamp`]
+    ///
+    /// [`set_timestamp`]: fn.set_timestamp.html
+    pub fn timestamp(mut self, enabled: bool) -> Config {
+        self.timestamp = Some(enabled);
+        self
+    }
+
+    /// Like [`set_thread_name`]
+    ///
+    /// [`set_thread_name`]: fn.set_thread_name.html
+    pub fn thread_name(mut self, enabled: bool) -> Config {
+        self.thread_name = Some(enabled);
+        self
+    }
+
+    /// Like [`set_quiet`]
+    ///
+    /// [`set_quiet`]: fn.set_quiet.html
+    pub fn quiet(mut self, enabled: bool) -> Config {
+        self.quiet = Some(enabled);
+        self
+    }
+
+    /// Like [`set_no_exit`]
+    ///
+    /// [`set_no_exit`]: fn.set_no_exit.html
+    pub fn no_exit(mut self, enabled: bool) -> Config {
+        self.no_exit = Some(enabled);
+        self
+    }
+
+    /// Like [`set_pause`]
+    ///
+    /// [`set_pause`]: fn.set_pause.html
+    pub fn pause(mut self, enabled: bool) -> Config {
+        self.pause = Some(enabled);
+        self
+    }
+
+    /// Like [`set_exit_delay`]
+    ///
+    /// [`set_exit_delay`]: fn.set_exit_delay.html
+    pub fn exit_delay(mut self, delay: Duration) -> Config {
+        self.exit_delay = Some(delay);
+        self
+    }
+
+    /// Like [`set_verbosity`]
+    ///
+    /// [`set_verbosity`]: fn.set_verbosity.html
+    pub fn verbosity(mut self, level: u8) -> Config {
+        self.verbosity = Some(level);
+        self
+    }
+
+    /// Like [`set_crash_report`]
+    ///
+    /// [`set_crash_report`]: fn.set_crash_report.html
+    pub fn crash_report(mut self, version: impl Into<String>) -> Config {
+        self.crash_report = Some(version.into());
+        self
+    }
+
+    /// Like [`set_log_file`]
+    ///
+    /// [`set_log_file`]: fn.set_log_file.html
+    pub fn log_file(mut self, path: impl Into<PathBuf>) -> Config {
+        self.log_file = Some(path.into());
+        self
+    }
+
+    /// Like [`set_crash_log_dir`]
+    ///
+    /// [`set_crash_log_dir`]: fn.set_crash_log_dir.html
+    pub fn crash_log_dir(mut self, dir: impl Into<PathBuf>, max: usize) -> Config {
+        self.crash_log_dir = Some((dir.into(), max));
+        self
+    }
+
+    /// Like [`set_json`]
+    ///
+    /// [`set_json`]: fn.set_json.html
+    #[cfg(feature = "json")]
+    pub fn json(mut self, enabled: bool) -> Config {
+        self.json = Some(enabled);
+        self
+    }
+
+    /// Like [`set_logfmt`]
+    ///
+    /// [`set_logfmt`]: fn.set_logfmt.html
+    #[cfg(feature = "logfmt")]
+    pub fn logfmt(mut self, enabled: bool) -> Config {
+        self.logfmt = Some(enabled);
+        self
+    }
+
+    /// Like [`set_logcat_tag`]
+    ///
+    /// [`set_logcat_tag`]: fn.set_logcat_tag.html
+    #[cfg(feature = "logcat")]
+    pub fn logcat_tag(mut self, tag: impl Into<String>) -> Config {
+        self.logcat_tag = Some(tag.into());
+        self
+    }
+
+    /// Like [`set_event_source`]
+    ///
+    /// [`set_event_source`]: fn.set_event_source.html
+    #[cfg(feature = "eventlog")]
+    pub fn event_source(mut self, source: impl Into<String>) -> Config {
+        self.event_source = Some(source.into());
+        self
+    }
+
+    /// Like [`set_shutdown_timeout`]
+    ///
+    /// [`set_shutdown_timeout`]: fn.set_shutdown_timeout.html
+    #[cfg(feature = "tokio")]
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> Config {
+        self.shutdown_timeout = Some(timeout);
+        self
+    }
+
+    /// Applies the settings of this builder
+    pub fn apply(self) {
+        if let Some(code) = self.default_exit_code {
+            exit_code::set_default_exit_code(code);
+        }
+        if let Some(enabled) = self.clamp_exit_code {
+            CLAMP_EXIT_CODE.store(enabled, Ordering::Relaxed);
+        }
+        if let Some(color) = self.color {
+            COLOR.store(color as u8, Ordering::Relaxed);
+        }
+        if let Some(enabled) = self.backtrace {
+            set_backtrace(enabled);
+        }
+        if let Some(enabled) = self.location {
+            set_location(enabled);
+        }
+        if let Some(enabled) = self.perl_style {
+            set_perl_style(enabled);
+        }
+        if let Some(enabled) = self.error_label {
+            set_error_label(enabled);
+        }
+        if let Some(name) = self.program_name {
+            set_program_name(name);
+        }
+        if let Some(enabled) = self.program_prefix {
+            set_program_prefix(enabled);
+        }
+        if let Some(enabled) = self.flush_stdout {
+            set_flush_stdout(enabled);
+        }
+        if let Some(enabled) = self.lock_stdout {
+            set_lock_stdout(enabled);
+        }
+        if let Some(enabled) = self.timestamp {
+            set_timestamp(enabled);
+        }
+        if let Some(enabled) = self.thread_name {
+            set_thread_name(enabled);
+        }
+        if let Some(enabled) = self.quiet {
+            set_quiet(enabled);
+        }
+        if let Some(enabled) = self.no_exit {
+            set_no_exit(enabled);
+        }
+        if let Some(enabled) = self.pause {
+            set_pause(enabled);
+        }
+        if let Some(delay) = self.exit_delay {
+            set_exit_delay(delay);
+        }
+        if let Some(level) = self.verbosity {
+            set_verbosity(level);
+        }
+        if let Some(version) = self.crash_report {
+            crash_report::set_crash_report(version);
+        }
+        if let Some(path) = self.log_file {
+            log_file::set_log_file(path);
+        }
+        if let Some((dir, max)) = self.crash_log_dir {
+            crash_log::set_crash_log_dir(dir, max);
+        }
+        #[cfg(feature = "json")]
+        if let Some(enabled) = self.json {
+            set_json(enabled);
+        }
+        #[cfg(feature = "logfmt")]
+        if let Some(enabled) = self.logfmt {
+            set_logfmt(enabled);
+        }
+        #[cfg(feature = "logcat")]
+        if let Some(tag) = self.logcat_tag {
+            set_logcat_tag(tag);
+        }
+        #[cfg(feature = "eventlog")]
+        if let Some(source) = self.event_source {
+            set_event_source(source);
+        }
+        #[cfg(feature = "tokio")]
+        if let Some(timeout) = self.shutdown_timeout {
+            shutdown::set_shutdown_timeout(timeout);
+        }
+    }
+}
+
+/// When to color output, set with [`Config::color`]
+///
+/// Coloring needs the `color` feature, without it output is never colored.
+///
+/// [`Config::color`]: struct.Config.html#method.color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    /// Color unless stderr is not a terminal, `NO_COLOR` is set or `CLICOLOR` is `0`, the default
+    Auto = 0,
+    /// Always color
+    Always = 1,
+    /// Never color
+    Never = 2,
+}
+
+#[cfg(feature = "color")]
+pub(crate) fn color() -> Color {
+    match COLOR.load(Ordering::Relaxed) {
+        1 => Color::Always,
+        2 => Color::Never,
+        _ => Color::Auto,
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn clamp_exit_code() -> bool {
+    CLAMP_EXIT_CODE.load(Ordering::Relaxed)
+}
+
+/// Sets whether a [`Backtrace`] is captured on every die and printed after the message, off by
+/// default
+///
+/// Capturing still honors the `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE` environment variables
+/// like [`Backtrace::capture`] does, so with this enabled a backtrace is only printed when one of
+/// them is set to `1` or `full`. Custom hooks can get it from [`DieInfo::backtrace`].
+///
+/// [`Backtrace`]: https://doc.rust-lang.org/std/backtrace/struct.Backtrace.html
+/// [`Backtrace::capture`]: https://doc.rust-lang.org/std/backtrace/struct.Backtrace.html#method.capture
+/// [`DieInfo::backtrace`]: struct.DieInfo.html#method.backtrace
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_backtrace(true);
+/// die!("corrupt index"); // prints `corrupt index` then, if RUST_BACKTRACE=1, a backtrace, then exits with code 1
+/// ```
+pub fn set_backtrace(enabled: bool) {
+    BACKTRACE.store(enabled, Ordering::Relaxed);
+}
+
+/// Captures a backtrace if enabled with [`set_backtrace`] and by the environment, or always at
+/// [`set_verbosity`] 3
+///
+/// [`set_backtrace`]: fn.set_backtrace.html
+/// [`set_verbosity`]: fn.set_verbosity.html
+pub(crate) fn capture_backtrace() -> Option<Backtrace> {
+    if verbosity() >= 3 {
+        return Some(Backtrace::force_capture());
+    }
+    if !BACKTRACE.load(Ordering::Relaxed) {
+        return None;
+    }
+    let backtrace = Backtrace::capture();
+    match backtrace.status() {
+        BacktraceStatus::Captured => Some(backtrace),
+        _ => None,
+    }
+}
+
+/// Sets whether the source location of the call that died is appended to the message, like
+/// ` (at src/main.rs:42)`, off by default
+///
+/// Handy to tell apart many identical `die("io error")` calls. The location is always available
+/// to custom hooks from [`DieInfo::location`].
+///
+/// [`DieInfo::location`]: struct.DieInfo.html#method.location
+///
+/// # Examples
+///
+/// ```should_panic
+/// use die::Die;
+/// die::set_location(true);
+/// let _: String = None.die("io error"); // prints `io error (at src/main.rs:4)` then exits with code 1
+/// ```
+pub fn set_location(enabled: bool) {
+    LOCATION.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn location() -> bool {
+    LOCATION.load(Ordering::Relaxed) || verbosity() >= 2
+}
+
+/// Sets whether messages start with an RFC 3339 UTC timestamp, like
+/// `2024-05-01T12:34:56.789Z no such file`, off by default
+///
+/// For long running daemons whose stderr lands in a file, where when it died matters. Warnings
+/// get one too, the JSON and logfmt output always have one.
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_timestamp(true);
+/// die!("no such file"); // prints `2024-05-01T12:34:56.789Z no such file` then exits with code 1
+/// ```
+pub fn set_timestamp(enabled: bool) {
+    TIMESTAMP.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn timestamp() -> bool {
+    TIMESTAMP.load(Ordering::Relaxed)
+}
+
+/// Sets whether messages from threads other than the main one are prefixed with the thread name,
+/// or its id if it has none, like `thread 'worker-3': no such file`, off by default
+///
+/// Like panic messages do, so it is clear which worker hit the fatal condition.
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_thread_name(true);
+/// std::thread::Builder::new()
+///     .name("worker-3".into())
+///     .spawn(|| die!("no such file")) // prints `thread 'worker-3': no such file` then exits with code 1
+///     .unwrap()
+///     .join()
+///     .unwrap();
+/// ```
+pub fn set_thread_name(enabled: bool) {
+    THREAD_NAME.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn thread_name() -> bool {
+    THREAD_NAME.load(Ordering::Relaxed)
+}
+
+/// Sets how much detail is printed, from `0` to `3`, so `-v` flags can be wired straight in, `1`
+/// by default
+///
+/// Each level adds to the one before it:
+///
+/// * `0`: only the message, errors without their `caused by:` lines
+/// * `1`: the `caused by:` lines of errors
+/// * `2`: the location, like [`set_location`]
+/// * `3`: a backtrace, regardless of `RUST_BACKTRACE`
+///
+/// Levels above `3` are treated as `3`. [`set_location`] and [`set_backtrace`] still turn on
+/// their detail at lower levels.
+///
+/// [`set_location`]: fn.set_location.html
+/// [`set_backtrace`]: fn.set_backtrace.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// let verbose = 2; // from counting -v flags
+/// die::set_verbosity(verbose);
+/// die!("no such file"); // prints `no such file (at src/main.rs:5)` then exits with code 1
+/// ```
+pub fn set_verbosity(level: u8) {
+    VERBOSITY.store(level.min(3), Ordering::Relaxed);
+}
+
+pub(crate) fn verbosity() -> u8 {
+    VERBOSITY.load(Ordering::Relaxed)
+}
+
+/// Sets whether messages follow Perl's `die` semantics, off by default
+///
+/// A message that doesn't end in a newline gets ` at <file> line <line>.` appended, one that
+/// does is printed verbatim, so it is to be used instead of [`set_location`].
+///
+/// [`set_location`]: fn.set_location.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_perl_style(true);
+/// if std::env::args().count() > 99 {
+///     die!("usage: prog FILE\n"); // prints `usage: prog FILE` then exits with code 1
+/// }
+/// die!("cannot open file"); // prints `cannot open file at src/main.rs line 6.` then exits with code 1
+/// ```
+pub fn set_perl_style(enabled: bool) {
+    PERL_STYLE.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn perl_style() -> bool {
+    PERL_STYLE.load(Ordering::Relaxed)
+}
+
+/// Sets whether messages are prefixed with the program name, like `myprog: no such file`, the
+/// Unix convention, off by default
+///
+/// The name is the file name of `argv[0]`, unless one was set with [`set_program_name`].
+///
+/// [`set_program_name`]: fn.set_program_name.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_program_prefix(true);
+/// die!("no such file"); // prints `myprog: no such file` then exits with code 1
+/// ```
+pub fn set_program_prefix(enabled: bool) {
+    PROGRAM_PREFIX.store(enabled, Ordering::Relaxed);
+}
+
+/// Sets the program name messages are prefixed with, instead of the file name of `argv[0]`, and
+/// turns the prefix on like [`set_program_prefix`]
+///
+/// [`set_program_prefix`]: fn.set_program_prefix.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_program_name(env!("CARGO_PKG_NAME"));
+/// die!("no such file"); // prints `die: no such file` then exits with code 1
+/// ```
+pub fn set_program_name(name: impl Into<String>) {
+    *PROGRAM_NAME.write().unwrap_or_else(PoisonError::into_inner) = Some(name.into());
+    set_program_prefix(true);
+}
+
+/// The program name to prefix messages with, if enabled
+pub(crate) fn program_prefix() -> Option<String> {
+    if !PROGRAM_PREFIX.load(Ordering::Relaxed) {
+        return None;
+    }
+    program_name()
+}
+
+/// The name set with [`set_program_name`], or else the file name the program was run as
+///
+/// [`set_program_name`]: fn.set_program_name.html
+pub(crate) fn program_name() -> Option<String> {
+    if let Some(ref name) = *PROGRAM_NAME.read().unwrap_or_else(PoisonError::into_inner) {
+        return Some(name.clone());
+    }
+    let argv0 = env::args_os().next()?;
+    let name = Path::new(&argv0).file_name()?;
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Sets whether messages printed to stderr by the default hook start with an `error: ` label,
+/// cargo and rustc style, off by default
+///
+/// With the `color` feature the label is bold red, unless stderr is not a terminal, `NO_COLOR`
+/// is set or `CLICOLOR` is `0`. Only the default hook prints it, so it never ends up in logs.
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_error_label(true);
+/// die!("no such file"); // prints `error: no such file` then exits with code 1
+/// ```
+pub fn set_error_label(enabled: bool) {
+    ERROR_LABEL.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn error_label() -> bool {
+    ERROR_LABEL.load(Ordering::Relaxed)
+}
+
+/// Sets whether stdout is flushed before the message is printed and the process exits, on by
+/// default
+///
+/// Anything still buffered in [`stdout`] is written out first, so it comes before the message,
+/// instead of after it or, for writers that aren't flushed at exit, never.
+///
+/// [`stdout`]: https://doc.rust-lang.org/std/io/fn.stdout.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_flush_stdout(true);
+/// print!("partial line ");
+/// die!("no such file"); // prints `partial line ` to stdout, then `no such file` to stderr then exits with code 1
+/// ```
+pub fn set_flush_stdout(enabled: bool) {
+    FLUSH_STDOUT.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn flush_stdout() -> bool {
+    FLUSH_STDOUT.load(Ordering::Relaxed)
+}
+
+/// Sets whether stdout stays locked from the flush until the process exits, so other threads
+/// can't print anything after the message, off by default
+///
+/// Only has an effect when stdout is flushed, see [`set_flush_stdout`], and never with the
+/// `panic` feature, where the process keeps running.
+///
+/// [`set_flush_stdout`]: fn.set_flush_stdout.html
+pub fn set_lock_stdout(enabled: bool) {
+    LOCK_STDOUT.store(enabled, Ordering::Relaxed);
+}
+
+#[cfg(not(feature = "panic"))]
+pub(crate) fn lock_stdout() -> bool {
+    LOCK_STDOUT.load(Ordering::Relaxed)
+}
+
+/// Sets whether messages are left out, so dying only exits with the code, off by default
+///
+/// For scripted invocations where stderr noise is unwanted but exit codes still matter. The
+/// default hook and [`warn`]!() print nothing, a custom hook still runs and decides for itself.
+/// Setting the `DIE_QUIET` environment variable to anything but `0` or nothing turns it on
+/// without a rebuild.
+///
+/// [`warn`]: macro.warn.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_quiet(true);
+/// die!("no such file"; 2); // prints nothing, only exits with code 2
+/// ```
+pub fn set_quiet(enabled: bool) {
+    QUIET.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+        || env::var_os("DIE_QUIET").is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Sets whether dying unwinds with a [`Died`] payload after printing the message instead of
+/// exiting, off by default
+///
+/// For orchestration frameworks, fuzzing drivers and language bindings that run a binary's logic
+/// in-process and must not be killed by it: they [`catch_unwind`] and [`downcast`] the payload to
+/// get the exit code and message. Like with the `panic` feature the default panic hook isn't
+/// invoked, cleanups run as usual, but the process goes on. Setting the `DIE_NO_EXIT` environment
+/// variable to anything but `0` or nothing turns it on without a rebuild. With the `panic`
+/// feature dying always unwinds anyway.
+///
+/// [`Died`]: struct.Died.html
+/// [`catch_unwind`]: https://doc.rust-lang.org/std/panic/fn.catch_unwind.html
+/// [`downcast`]: https://doc.rust-lang.org/std/boxed/struct.Box.html#method.downcast
+///
+/// # Examples
+///
+/// ```
+/// # use die::die;
+/// die::set_no_exit(true);
+/// let payload = std::panic::catch_unwind(|| die!(4; "bad input")).unwrap_err(); // prints `bad input` to stderr
+/// let died = payload.downcast::<die::Died>().unwrap();
+/// assert_eq!(died.code(), 4);
+/// ```
+pub fn set_no_exit(enabled: bool) {
+    NO_EXIT.store(enabled, Ordering::Relaxed);
+}
+
+#[cfg(not(feature = "panic"))]
+pub(crate) fn no_exit() -> bool {
+    NO_EXIT.load(Ordering::Relaxed)
+        || env::var_os("DIE_NO_EXIT").is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Sets whether dying waits for Enter after printing the message when the console window would
+/// close with the process, off by default
+///
+/// Double-clicking a console program on Windows opens a console just for it, which closes the
+/// moment it exits, before the message can be read. So with this enabled, when stdin and stderr
+/// are both a console only this process is attached to, die prints `press Enter to exit` and
+/// waits, right before exiting. A console shared with the shell the program was started from is
+/// left alone. Elsewhere there is no telling, so it waits in any terminal. Nothing is waited for
+/// without a message, or with the `panic` feature or [`set_no_exit`]. Setting the `DIE_PAUSE`
+/// environment variable to anything but `0` or nothing turns it on without a rebuild.
+///
+/// [`set_no_exit`]: fn.set_no_exit.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_pause(true);
+/// die!("config.toml not found"); // prints `config.toml not found`, then `press Enter to exit` when double-clicked, then exits with code 1
+/// ```
+pub fn set_pause(enabled: bool) {
+    PAUSE.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn pause() -> bool {
+    PAUSE.load(Ordering::Relaxed)
+        || env::var_os("DIE_PAUSE").is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Sets how long dying sleeps right before exiting, once everything is printed and the hooks,
+/// integrations and cleanups have run, none by default
+///
+/// Gives async log forwarders, tracing workers and crash reporters that ship in the background
+/// time to drain before they are killed with the process. Never slept with the `panic` feature or
+/// [`set_no_exit`], as the process goes on.
+///
+/// [`set_no_exit`]: fn.set_no_exit.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_exit_delay(std::time::Duration::from_millis(200));
+/// die!("lost connection"); // prints `lost connection`, waits 200ms, then exits with code 1
+/// ```
+pub fn set_exit_delay(delay: Duration) {
+    EXIT_DELAY_MS.store(delay.as_millis() as u64, Ordering::Relaxed);
+}
+
+pub(crate) fn exit_delay() -> Duration {
+    Duration::from_millis(EXIT_DELAY_MS.load(Ordering::Relaxed))
+}
+
+/// Sets whether the default hook prints a single line JSON object instead of plain text, for log
+/// pipelines that need structured stderr, off by default
+///
+/// The object looks like `{"level":"fatal","message":"no such file","code":1,
+/// "timestamp":"2024-05-01T12:34:56.789Z"}`, with a `"backtrace"` too if one was captured. The
+/// `"message"` is left out when dying with only a code.
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_json(true);
+/// die!("no such file"; 2); // prints `{"level":"fatal","message":"no such file","code":2,"timestamp":"..."}` then exits with code 2
+/// ```
+#[cfg(feature = "json")]
+pub fn set_json(enabled: bool) {
+    JSON.store(enabled, Ordering::Relaxed);
+}
+
+#[cfg(feature = "json")]
+pub(crate) fn json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Sets whether the default hook prints a single logfmt line instead of plain text, for log
+/// shippers that prefer logfmt over JSON, off by default
+///
+/// The line looks like `level=fatal code=1 msg="no such file" ts=2024-05-01T12:34:56.789Z`, with
+/// a `backtrace` too if one was captured. The `msg` is left out when dying with only a code. JSON
+/// wins if both are enabled.
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_logfmt(true);
+/// die!("no such file"; 2); // prints `level=fatal code=2 msg="no such file" ts=...` then exits with code 2
+/// ```
+#[cfg(feature = "logfmt")]
+pub fn set_logfmt(enabled: bool) {
+    LOGFMT.store(enabled, Ordering::Relaxed);
+}
+
+#[cfg(feature = "logfmt")]
+pub(crate) fn logfmt() -> bool {
+    LOGFMT.load(Ordering::Relaxed)
+}
+
+/// Sets the tag messages are logged to logcat with on Android, the program name by default
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_logcat_tag("myapp");
+/// die!("no such file"; 2); // logs `no such file` with priority ERROR and tag `myapp` on Android, then exits with code 2
+/// ```
+#[cfg(feature = "logcat")]
+pub fn set_logcat_tag(tag: impl Into<String>) {
+    *LOGCAT_TAG.write().unwrap_or_else(PoisonError::into_inner) = Some(tag.into());
+}
+
+/// The tag set with [`set_logcat_tag`], or else the program name
+///
+/// [`set_logcat_tag`]: fn.set_logcat_tag.html
+#[cfg(all(feature = "logcat", target_os = "android"))]
+pub(crate) fn logcat_tag() -> String {
+    if let Some(ref tag) = *LOGCAT_TAG.read().unwrap_or_else(PoisonError::into_inner) {
+        return tag.clone();
+    }
+    program_name().unwrap_or_else(|| "die".to_string())
+}
+
+/// Sets the source name die events are recorded under in the Windows Event Log, the program name
+/// by default
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_event_source("MyService");
+/// die!("no such file"; 2); // records an error event from `MyService` on Windows, then exits with code 2
+/// ```
+#[cfg(feature = "eventlog")]
+pub fn set_event_source(source: impl Into<String>) {
+    *EVENT_SOURCE.write().unwrap_or_else(PoisonError::into_inner) = Some(source.into());
+}
+
+/// The source set with [`set_event_source`], or else the program name
+///
+/// [`set_event_source`]: fn.set_event_source.html
+#[cfg(all(feature = "eventlog", windows))]
+pub(crate) fn event_source() -> String {
+    if let Some(ref source) = *EVENT_SOURCE.read().unwrap_or_else(PoisonError::into_inner) {
+        return source.clone();
+    }
+    program_name().unwrap_or_else(|| "die".to_string())
+}
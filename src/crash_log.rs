@@ -0,0 +1,197 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use encode::{Str, Timestamp};
+use {config, DieInfo};
+
+static DIR: RwLock<Option<(PathBuf, usize)>> = RwLock::new(None);
+/// The millisecond and suffix of the last record written, so a later one in the same millisecond
+/// gets a higher suffix even once rotation deleted the earlier ones
+static LAST: Mutex<(u128, u32)> = Mutex::new((0, 0));
+
+/// Writes every die as a file of its own into the directory `dir`, creating it if needed, and
+/// keeps only the `max` most recent ones, or all of them if `max` is `0`, so support can ask users
+/// for their last few crashes
+///
+/// Each file is a small TOML document named like `myprog-001714566896789-1234.toml`, after the
+/// program name, the zero padded time in milliseconds and the process id, with a `-1`, `-2`...
+/// suffix for later dies in the same millisecond, holding the timestamp, the exit code, the
+/// message if there is one, the location and a backtrace if one was captured, see
+/// [`set_backtrace`]. Existing files are never overwritten, and only files named exactly like
+/// that are rotated, so other programs and files can share the directory. Failing to write or
+/// rotate them is ignored.
+///
+/// [`set_backtrace`]: fn.set_backtrace.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_crash_log_dir(std::env::temp_dir().join("myprog-crashes"), 5);
+/// die!("corrupt index"); // prints `corrupt index`, writes it to a new file in the directory, keeping the last 5, then exits with code 1
+/// ```
+///
+/// Keeping the last 3 of 5 dies, caught with [`set_no_exit`]:
+///
+/// ```
+/// # use die::die;
+/// # use std::fs;
+/// let dir = std::env::temp_dir().join(format!("die-crash-log-{}", std::process::id()));
+/// fs::create_dir_all(&dir).unwrap();
+/// fs::write(dir.join("myprog-helper-001714566896789-1234.toml"), "").unwrap();
+/// fs::write(dir.join("notes.toml"), "").unwrap();
+///
+/// die::set_program_name("myprog");
+/// die::set_program_prefix(false);
+/// die::set_no_exit(true);
+/// die::set_crash_log_dir(&dir, 3);
+/// for i in 0..5 {
+///     let _ = std::panic::catch_unwind(|| die!("crash {}", i));
+/// }
+///
+/// let mut kept = Vec::new();
+/// for entry in fs::read_dir(&dir).unwrap() {
+///     let path = entry.unwrap().path();
+///     let name = path.file_name().unwrap().to_str().unwrap();
+///     if name.starts_with("myprog-0") {
+///         let record = fs::read_to_string(&path).unwrap();
+///         kept.push(record.lines().find(|l| l.starts_with("message")).unwrap().to_string());
+///     }
+/// }
+/// kept.sort();
+/// assert_eq!(kept, ["message = \"crash 2\"", "message = \"crash 3\"", "message = \"crash 4\""]);
+/// assert!(dir.join("myprog-helper-001714566896789-1234.toml").exists());
+/// assert!(dir.join("notes.toml").exists());
+/// fs::remove_dir_all(&dir).unwrap();
+/// ```
+///
+/// [`set_no_exit`]: fn.set_no_exit.html
+pub fn set_crash_log_dir(dir: impl Into<PathBuf>, max: usize) {
+    *DIR.write().unwrap_or_else(PoisonError::into_inner) = Some((dir.into(), max));
+}
+
+/// Writes `info` to a new file in the crash log directory and rotates it, if one is set
+pub(crate) fn write(info: &DieInfo) {
+    let dir = DIR.read().unwrap_or_else(PoisonError::into_inner);
+    let (dir, max) = match *dir {
+        Some((ref dir, max)) => (dir, max),
+        None => return,
+    };
+    let name = config::program_name().unwrap_or_else(|| "program".to_string());
+    let now = SystemTime::now();
+    let millis = now
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let _ = fs::create_dir_all(dir).and_then(|()| {
+        let mut file = create(dir, &name, millis)?;
+        write_record(&mut file, info, now)?;
+        file.flush()
+    });
+    if max > 0 {
+        let _ = rotate(dir, &name, max);
+    }
+}
+
+/// Creates a new record file, with a suffix if another die in the same millisecond came first,
+/// never overwriting one
+fn create(dir: &Path, name: &str, millis: u128) -> io::Result<File> {
+    let pid = process::id();
+    let mut last = LAST.lock().unwrap_or_else(PoisonError::into_inner);
+    let first = match *last {
+        (last_millis, suffix) if last_millis == millis => suffix + 1,
+        _ => 0,
+    };
+    let mut attempt = first;
+    loop {
+        // zero padded so the names line up
+        let path = match attempt {
+            0 => dir.join(format!("{}-{:015}-{}.toml", name, millis, pid)),
+            _ => dir.join(format!("{}-{:015}-{}-{}.toml", name, millis, pid, attempt)),
+        };
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Err(ref err) if err.kind() == ErrorKind::AlreadyExists && attempt < first + 100 => {
+                attempt += 1
+            }
+            Ok(file) => {
+                *last = (millis, attempt);
+                return Ok(file);
+            }
+            err => return err,
+        }
+    }
+}
+
+fn write_record(out: &mut dyn Write, info: &DieInfo, now: SystemTime) -> io::Result<()> {
+    writeln!(out, "timestamp = \"{}\"", Timestamp(now))?;
+    writeln!(out, "code = {}", info.code())?;
+    if let Some(message) = info.message() {
+        writeln!(out, "message = {}", Str(&message.to_string()))?;
+    }
+    let location = info.location();
+    let location = format!(
+        "{}:{}:{}",
+        location.file(),
+        location.line(),
+        location.column()
+    );
+    writeln!(out, "location = {}", Str(&location))?;
+    if let Some(backtrace) = info.backtrace() {
+        writeln!(out, "backtrace = {}", Str(&backtrace.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Removes the oldest records of `name` in `dir` until only `max` are left
+fn rotate(dir: &Path, name: &str, max: usize) -> io::Result<()> {
+    let mut records = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let file_name = entry?.file_name();
+        if let Some(key) = file_name
+            .to_str()
+            .and_then(|file_name| record(file_name, name))
+        {
+            records.push((key, file_name));
+        }
+    }
+    records.sort();
+    let excess = records.len().saturating_sub(max);
+    for (_, record) in &records[..excess] {
+        let _ = fs::remove_file(dir.join(record));
+    }
+    Ok(())
+}
+
+/// The time, process id and suffix of `file_name`, which sort in the order records were written,
+/// if it's a record of `name`, named `{name}-{15 digit millis}-{pid}.toml` or with a `-{n}` suffix
+fn record(file_name: &str, name: &str) -> Option<(u128, u32, u32)> {
+    let rest = file_name
+        .strip_prefix(name)?
+        .strip_prefix('-')?
+        .strip_suffix(".toml")?;
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let mut parts = rest.split('-');
+    let millis = parts
+        .next()
+        .filter(|millis| millis.len() == 15 && digits(millis))?;
+    let pid = parts.next().filter(|pid| digits(pid))?;
+    let attempt = match parts.next() {
+        Some(attempt) if digits(attempt) => attempt.parse().ok()?,
+        Some(_) => return None,
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((millis.parse().ok()?, pid.parse().ok()?, attempt))
+}
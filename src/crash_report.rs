@@ -0,0 +1,116 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::backtrace::Backtrace;
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::PathBuf;
+use std::process;
+use std::sync::{PoisonError, RwLock};
+use std::time::SystemTime;
+
+use encode::{Str, Timestamp};
+use {config, output, DieInfo};
+
+static VERSION: RwLock<Option<String>> = RwLock::new(None);
+
+/// Turns on crash reports, human-panic style, for a program at `version`, usually
+/// `env!("CARGO_PKG_VERSION")`
+///
+/// Every die with a message, and every panic once [`install_die_panic_hook`] is installed, then
+/// writes a TOML report with the program name and version, the operating system, the message,
+/// the exit code, the location and a backtrace to the temp directory, and tells the user where
+/// it is so they have something to attach when reporting the crash.
+///
+/// [`install_die_panic_hook`]: fn.install_die_panic_hook.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_crash_report(env!("CARGO_PKG_VERSION"));
+/// die!("corrupt index"); // prints `corrupt index`, then `a crash report was written to /tmp/myprog-report-1234.toml, please attach it when reporting this`, then exits with code 1
+/// ```
+pub fn set_crash_report(version: impl Into<String>) {
+    *VERSION.write().unwrap_or_else(PoisonError::into_inner) = Some(version.into());
+}
+
+/// Writes the crash report and tells the user about it, if enabled and there is a message
+pub(crate) fn write(info: &DieInfo) {
+    let version = match *VERSION.read().unwrap_or_else(PoisonError::into_inner) {
+        Some(ref version) => version.clone(),
+        None => return,
+    };
+    if info.message().is_none() {
+        return;
+    }
+    let name = config::program_name().unwrap_or_else(|| "program".to_string());
+    let (path, file) = create(&name);
+    let written = file.and_then(|mut file| {
+        write_report(&mut file, info, &name, &version)?;
+        file.flush()
+    });
+    output::with(info.stdout, |out, _| {
+        let _ = match written {
+            Ok(()) => writeln!(
+                out,
+                "a crash report was written to {}, please attach it when reporting this",
+                path.display()
+            ),
+            Err(err) => writeln!(
+                out,
+                "writing a crash report to {} failed: {}",
+                path.display(),
+                err
+            ),
+        };
+        let _ = out.flush();
+    })
+}
+
+/// Creates a new report file in the temp directory, never opening one that already exists, so a
+/// file or symlink planted at the predictable path in a shared `/tmp` can't be overwritten
+fn create(name: &str) -> (PathBuf, io::Result<File>) {
+    let dir = env::temp_dir();
+    let pid = process::id();
+    let mut attempt = 0;
+    loop {
+        let path = match attempt {
+            0 => dir.join(format!("{}-report-{}.toml", name, pid)),
+            _ => dir.join(format!("{}-report-{}-{}.toml", name, pid, attempt)),
+        };
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Err(ref err) if err.kind() == ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+            file => return (path, file),
+        }
+    }
+}
+
+fn write_report(out: &mut dyn Write, info: &DieInfo, name: &str, version: &str) -> io::Result<()> {
+    writeln!(out, "name = {}", Str(name))?;
+    writeln!(out, "version = {}", Str(version))?;
+    writeln!(out, "operating_system = {}", Str(env::consts::OS))?;
+    writeln!(out, "arch = {}", Str(env::consts::ARCH))?;
+    writeln!(out, "timestamp = \"{}\"", Timestamp(SystemTime::now()))?;
+    if let Some(message) = info.message() {
+        writeln!(out, "message = {}", Str(&message.to_string()))?;
+    }
+    writeln!(out, "code = {}", info.code())?;
+    let location = info.location();
+    let location = format!(
+        "{}:{}:{}",
+        location.file(),
+        location.line(),
+        location.column()
+    );
+    writeln!(out, "location = {}", Str(&location))?;
+    let backtrace = match info.backtrace() {
+        Some(backtrace) => backtrace.to_string(),
+        None => Backtrace::force_capture().to_string(),
+    };
+    writeln!(out, "backtrace = {}", Str(&backtrace))
+}
@@ -0,0 +1,152 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::panic::Location;
+
+use {default_exit_code, exit, snippet, suggest, DieInfo, IntoExitCode};
+
+/// Starts building a rustc style diagnostic, a message followed by `note:` and `help:` lines,
+/// that dies when [`exit`] is called
+///
+/// [`exit`]: struct.Diagnostic.html#method.exit
+///
+/// # Examples
+///
+/// ```should_panic
+/// die::fatal("destination `out` already exists")
+///     .note("it was created by a previous run")
+///     .help("pass --force to overwrite it")
+///     .code(2)
+///     .exit();
+/// // prints
+/// // destination `out` already exists
+/// //   = note: it was created by a previous run
+/// //   = help: pass --force to overwrite it
+/// // to stderr then exits with code 2
+/// ```
+#[track_caller]
+pub fn fatal(message: impl fmt::Display) -> Diagnostic {
+    Diagnostic {
+        message: message.to_string(),
+        snippet: None,
+        lines: Vec::new(),
+        code: None,
+        location: Location::caller(),
+    }
+}
+
+/// A diagnostic built with [`fatal`]
+///
+/// [`fatal`]: fn.fatal.html
+#[derive(Debug, Clone)]
+#[must_use = "the diagnostic does nothing until exit is called"]
+pub struct Diagnostic {
+    message: String,
+    snippet: Option<(usize, String, usize)>,
+    lines: Vec<(&'static str, String)>,
+    /// Looked up in exit unless set, so building one that never exits costs nothing
+    code: Option<i32>,
+    location: &'static Location<'static>,
+}
+
+impl Diagnostic {
+    /// Adds a `note:` line, for context on what went wrong
+    pub fn note(mut self, note: impl fmt::Display) -> Diagnostic {
+        self.lines.push(("note", note.to_string()));
+        self
+    }
+
+    /// Adds a `help:` line, for what the user can do about it
+    pub fn help(mut self, help: impl fmt::Display) -> Diagnostic {
+        self.lines.push(("help", help.to_string()));
+        self
+    }
+
+    /// Shows line `line` of `source` below the message, with a caret under column `column`, like
+    /// [`snippet`] does
+    ///
+    /// [`snippet`]: fn.snippet.html
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// let source = "name = \"die\"\nport 8080\n";
+    /// die::fatal("expected `=` after `port`")
+    ///     .snippet(source, 2, 6)
+    ///     .help("write it as `port = 8080`")
+    ///     .code(65)
+    ///     .exit();
+    /// // prints
+    /// // expected `=` after `port`
+    /// //   |
+    /// // 2 | port 8080
+    /// //   |      ^
+    /// //   = help: write it as `port = 8080`
+    /// // to stderr then exits with code 65
+    /// ```
+    pub fn snippet(mut self, source: &str, line: usize, column: usize) -> Diagnostic {
+        self.snippet = line
+            .checked_sub(1)
+            .and_then(|i| source.lines().nth(i))
+            .map(|text| (line, text.to_string(), column));
+        self
+    }
+
+    /// Adds a `help: did you mean `...`?` line with the candidate closest to a mistyped `input`,
+    /// if any is close enough, see [`suggest`]
+    ///
+    /// [`suggest`]: fn.suggest.html
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// let input = "instal";
+    /// die::fatal(format_args!("unknown command `{}`", input))
+    ///     .suggest(input, ["install", "update"])
+    ///     .code(2)
+    ///     .exit(); // prints `unknown command `instal``, then `  = help: did you mean `install`?` then exits with code 2
+    /// ```
+    pub fn suggest<'a>(
+        self,
+        input: &str,
+        candidates: impl IntoIterator<Item = &'a str>,
+    ) -> Diagnostic {
+        match suggest::suggest(input, candidates) {
+            Some(candidate) => self.help(format_args!("did you mean `{}`?", candidate)),
+            None => self,
+        }
+    }
+
+    /// Sets the exit code, the default exit code unless called
+    pub fn code(mut self, code: impl IntoExitCode) -> Diagnostic {
+        self.code = Some(code.into_exit_code());
+        self
+    }
+
+    /// Prints the diagnostic like [`die`]!() prints a message, then exits
+    ///
+    /// [`die`]: macro.die.html
+    pub fn exit(self) -> ! {
+        let code = self.code.unwrap_or_else(default_exit_code);
+        let mut info = DieInfo::new(Some(&self), code);
+        info.location = self.location;
+        exit(info)
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)?;
+        if let Some((line, ref text, column)) = self.snippet {
+            snippet::excerpt(f, line, text, column)?;
+        }
+        for &(label, ref line) in &self.lines {
+            write!(f, "\n  = {}: {}", label, line)?;
+        }
+        Ok(())
+    }
+}
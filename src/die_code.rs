@@ -0,0 +1,116 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::fmt;
+
+/// `DieCode` is a trait for error types that know which exit code and message they should die
+/// with, used by [`DieResult::die_auto`]
+///
+/// With the `derive` feature it can be derived, mapping each variant of an error enum to a code
+/// and message with `#[die(code = 65, msg = "...")]` attributes, see [`die_derive::DieCode`].
+///
+/// [`DieResult::die_auto`]: trait.DieResult.html#tymethod.die_auto
+/// [`die_derive::DieCode`]: https://docs.rs/die-derive/0.2/die_derive/derive.DieCode.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// use die::{codes, DieCode, DieResult};
+/// use std::fmt;
+///
+/// enum AppError {
+///     BadFlag,
+///     NoInput,
+/// }
+///
+/// impl DieCode for AppError {
+///     fn exit_code(&self) -> i32 {
+///         match *self {
+///             AppError::BadFlag => codes::EX_USAGE,
+///             AppError::NoInput => codes::EX_NOINPUT,
+///         }
+///     }
+///
+///     fn die_message(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         match *self {
+///             AppError::BadFlag => f.write_str("unknown flag, see --help"),
+///             AppError::NoInput => f.write_str("input file not found"),
+///         }
+///     }
+/// }
+///
+/// let x: Result<u32, AppError> = Err(AppError::BadFlag);
+/// x.die_auto(); // prints `unknown flag, see --help` to stderr then exits with code 64
+/// ```
+pub trait DieCode {
+    /// The code to exit with
+    fn exit_code(&self) -> i32;
+
+    /// Writes the message to print before exiting
+    fn die_message(&self, f: &mut fmt::Formatter) -> fmt::Result;
+}
+
+/// `ExitCodeFromError` is a shorter way to implement [`DieCode`] for errors whose [`Display`] is
+/// already the message, declaring only the exit code, so a library defines its exit semantics
+/// once instead of every call site choosing a number
+///
+/// Every type implementing it and [`Display`] implements [`DieCode`], so
+/// [`DieResult::die_auto`] prints the error then exits with its code.
+///
+/// [`DieCode`]: trait.DieCode.html
+/// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// [`DieResult::die_auto`]: trait.DieResult.html#tymethod.die_auto
+///
+/// # Examples
+///
+/// ```should_panic
+/// use die::{codes, DieResult, ExitCodeFromError};
+/// use std::fmt;
+///
+/// struct Timeout;
+///
+/// impl fmt::Display for Timeout {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         f.write_str("timed out")
+///     }
+/// }
+///
+/// impl ExitCodeFromError for Timeout {
+///     fn exit_code(&self) -> i32 {
+///         codes::EX_TEMPFAIL
+///     }
+/// }
+///
+/// let x: Result<u32, Timeout> = Err(Timeout);
+/// x.die_auto(); // prints `timed out` to stderr then exits with code 75
+/// ```
+pub trait ExitCodeFromError {
+    /// The code to exit with
+    fn exit_code(&self) -> i32;
+}
+
+impl<E: ExitCodeFromError + fmt::Display + ?Sized> DieCode for E {
+    #[inline]
+    fn exit_code(&self) -> i32 {
+        ExitCodeFromError::exit_code(self)
+    }
+
+    #[inline]
+    fn die_message(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Displays the [`DieCode::die_message`] of an error
+///
+/// [`DieCode::die_message`]: trait.DieCode.html#tymethod.die_message
+pub(crate) struct DieMessage<'a, E: 'a>(pub(crate) &'a E);
+
+impl<E: DieCode> fmt::Display for DieMessage<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.die_message(f)
+    }
+}
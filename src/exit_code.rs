@@ -0,0 +1,263 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::num::{NonZeroI32, NonZeroU8};
+use core::sync::atomic::{AtomicI32, Ordering};
+#[cfg(feature = "std")]
+use std::env;
+#[cfg(all(feature = "std", unix))]
+use std::os::unix::process::ExitStatusExt;
+#[cfg(feature = "std")]
+use std::process::{ExitCode, ExitStatus};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+use DEFAULT_EXIT_CODE;
+#[cfg(all(feature = "std", unix))]
+use {config, output};
+
+static DEFAULT: AtomicI32 = AtomicI32::new(DEFAULT_EXIT_CODE);
+
+/// The exit code used when none is given, [`DEFAULT_EXIT_CODE`] unless changed with
+/// [`Config::default_exit_code`]
+///
+/// With the `std` feature the `DIE_EXIT_CODE` environment variable overrides both, so operators
+/// can adjust the exit codes of a tool without rebuilding it. It is a comma separated list of a
+/// new default code and `from=to` pairs remapping specific codes, like `DIE_EXIT_CODE=3,64=2`.
+/// Invalid entries are ignored.
+///
+/// [`DEFAULT_EXIT_CODE`]: constant.DEFAULT_EXIT_CODE.html
+/// [`Config::default_exit_code`]: struct.Config.html#method.default_exit_code
+pub fn default_exit_code() -> i32 {
+    #[cfg(feature = "std")]
+    if let Some(code) = env_codes().default {
+        return code;
+    }
+    DEFAULT.load(Ordering::Relaxed)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn set_default_exit_code(code: i32) {
+    DEFAULT.store(code, Ordering::Relaxed);
+}
+
+/// Stands in for [`default_exit_code`] until the code is needed, so the likes of `Die::die` only
+/// look it up on the failure path
+#[derive(Clone, Copy)]
+pub(crate) struct DefaultExitCode;
+
+impl IntoExitCode for DefaultExitCode {
+    #[inline]
+    fn into_exit_code(self) -> i32 {
+        default_exit_code()
+    }
+}
+
+/// `DIE_EXIT_CODE`, parsed once
+#[cfg(feature = "std")]
+struct EnvCodes {
+    default: Option<i32>,
+    remap: Vec<(i32, i32)>,
+}
+
+#[cfg(feature = "std")]
+fn env_codes() -> &'static EnvCodes {
+    static ENV_CODES: OnceLock<EnvCodes> = OnceLock::new();
+    ENV_CODES.get_or_init(|| {
+        let mut codes = EnvCodes {
+            default: None,
+            remap: Vec::new(),
+        };
+        let var = env::var("DIE_EXIT_CODE").unwrap_or_default();
+        for entry in var.split(',').map(str::trim) {
+            match entry.split_once('=') {
+                Some((from, to)) => {
+                    if let (Ok(from), Ok(to)) = (from.trim().parse(), to.trim().parse()) {
+                        codes.remap.push((from, to));
+                    }
+                }
+                None => codes.default = entry.parse().ok().or(codes.default),
+            }
+        }
+        codes
+    })
+}
+
+/// Applies the `from=to` pairs of `DIE_EXIT_CODE`, see [`default_exit_code`]
+#[cfg(feature = "std")]
+pub(crate) fn remap(code: i32) -> i32 {
+    env_codes()
+        .remap
+        .iter()
+        .find(|&&(from, _)| from == code)
+        .map_or(code, |&(_, to)| to)
+}
+
+/// Conversion into the `i32` exit code passed to [`process::exit`], implemented for everything
+/// accepted as an exit code by [`die`]!() and the [`Die`] trait
+///
+/// Implement it for your own exit code enums to use them directly, they need to be `Copy` to go
+/// in the code position of [`die`]!().
+///
+/// [`process::exit`]: https://doc.rust-lang.org/std/process/fn.exit.html
+/// [`die`]: macro.die.html
+/// [`Die`]: trait.Die.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::{die, IntoExitCode};
+/// #[derive(Clone, Copy)]
+/// enum Code {
+///     Usage = 2,
+///     Config = 78,
+/// }
+///
+/// impl IntoExitCode for Code {
+///     fn into_exit_code(self) -> i32 {
+///         self as i32
+///     }
+/// }
+///
+/// die!(Code::Config; "no config"); // prints `no config` to stderr then exits with code 78
+/// ```
+pub trait IntoExitCode {
+    /// Converts this into an exit code
+    fn into_exit_code(self) -> i32;
+}
+
+impl IntoExitCode for i32 {
+    #[inline]
+    fn into_exit_code(self) -> i32 {
+        self
+    }
+}
+
+impl IntoExitCode for u8 {
+    #[inline]
+    fn into_exit_code(self) -> i32 {
+        i32::from(self)
+    }
+}
+
+impl IntoExitCode for NonZeroI32 {
+    #[inline]
+    fn into_exit_code(self) -> i32 {
+        self.get()
+    }
+}
+
+impl IntoExitCode for NonZeroU8 {
+    #[inline]
+    fn into_exit_code(self) -> i32 {
+        i32::from(self.get())
+    }
+}
+
+/// Windows exit codes are 32-bit unsigned, like the `0xC000013A` of a process ended by Ctrl+C, so
+/// `u32` codes are reinterpreted bit for bit as the `i32` [`process::exit`] takes, which Windows
+/// then reinterprets back, without losing anything. On unix only the low 8 bits reach the parent,
+/// so stick to `i32` codes in 0-255 for portable code.
+///
+/// [`process::exit`]: https://doc.rust-lang.org/std/process/fn.exit.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die!(0xC000_013Au32; "interrupted"); // prints `interrupted` then exits with code 0xC000013A on Windows
+/// ```
+impl IntoExitCode for u32 {
+    #[inline]
+    fn into_exit_code(self) -> i32 {
+        self as i32
+    }
+}
+
+/// [`ExitCode`] keeps its value opaque, but can only be built from a `u8` (or be one of
+/// [`SUCCESS`]/[`FAILURE`]), so the value is found by comparing against all of them.
+///
+/// [`ExitCode`]: https://doc.rust-lang.org/std/process/struct.ExitCode.html
+/// [`SUCCESS`]: https://doc.rust-lang.org/std/process/struct.ExitCode.html#associatedconstant.SUCCESS
+/// [`FAILURE`]: https://doc.rust-lang.org/std/process/struct.ExitCode.html#associatedconstant.FAILURE
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::Die;
+/// use std::process::ExitCode;
+/// let x: Option<u32> = None;
+/// x.die_code("strange", ExitCode::from(3)); // prints `strange` to stderr then exits with code 3
+/// ```
+#[cfg(feature = "std")]
+impl IntoExitCode for ExitCode {
+    fn into_exit_code(self) -> i32 {
+        if self == ExitCode::SUCCESS {
+            return 0;
+        }
+        (1..=u8::MAX)
+            .find(|&code| ExitCode::from(code) == self)
+            .map_or_else(default_exit_code, i32::from)
+    }
+}
+
+/// The exit code of a child process, or `128 + signal` if it was killed by a signal like shells
+/// report it, so a wrapper can exit the way the program it ran did
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// let status = std::process::Command::new("false").status().unwrap();
+/// if !status.success() {
+///     die!(status; "false failed"); // prints `false failed` to stderr then exits with code 1
+/// }
+/// ```
+#[cfg(feature = "std")]
+impl IntoExitCode for ExitStatus {
+    fn into_exit_code(self) -> i32 {
+        #[cfg(unix)]
+        if let Some(signal) = self.signal() {
+            return 128 + signal;
+        }
+        self.code().unwrap_or_else(default_exit_code)
+    }
+}
+
+/// Checks that `code` survives the trip to the parent process, on unix only the low 8 bits do, so
+/// `256` would look like success
+///
+/// Out of range codes print a warning in debug builds, and are clamped to `255` with a warning
+/// when enabled with [`Config::clamp_exit_code`].
+///
+/// [`Config::clamp_exit_code`]: struct.Config.html#method.clamp_exit_code
+#[cfg(all(feature = "std", unix))]
+pub(crate) fn check(code: i32) -> i32 {
+    if (0..=255).contains(&code) {
+        return code;
+    }
+    let clamp = config::clamp_exit_code();
+    let seen = if clamp { 255 } else { code & 0xff };
+    if clamp || cfg!(debug_assertions) {
+        output::with(false, |out, _| {
+            let _ = writeln!(
+                out,
+                "warning: exit code {} is outside 0-255, the parent will see {}",
+                code, seen
+            );
+        });
+    }
+    if clamp {
+        seen
+    } else {
+        code
+    }
+}
+
+#[cfg(all(feature = "std", not(unix)))]
+pub(crate) fn check(code: i32) -> i32 {
+    code
+}
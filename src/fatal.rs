@@ -0,0 +1,154 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::error::Error;
+use std::fmt;
+use std::process::{ExitCode, Termination};
+
+use broken_pipe;
+use chain::Chain;
+use {default_exit_code, print_exit, IntoExitCode};
+
+/// A fatal error carrying its message and exit code, for returning from `main` with `?` instead
+/// of calling [`die`]!() everywhere
+///
+/// Any [`Error`] converts into a `Fatal` with code 1 and the error's alternate [`Display`]
+/// output as the message, followed by a `caused by:` line for each of its sources, so `?` just
+/// works; use [`Fatal::new`] for a custom code.
+///
+/// `Fatal` implements [`Termination`] by dying with its message and code, so returning one from
+/// `main`, or from the closure passed to [`run`], prints the message cleanly through the die hook
+/// and exits with the embedded code. Note that std's own [`Termination`] for
+/// `Result<(), Fatal>` can't be changed: returning that from `main` prints `Error: ` followed by
+/// the message (no struct dump, [`Debug`] prints the message too) and always exits with code 1,
+/// so wrap the body with [`run`] to keep the code.
+///
+/// [`die`]: macro.die.html
+/// [`Error`]: https://doc.rust-lang.org/std/error/trait.Error.html
+/// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+/// [`Termination`]: https://doc.rust-lang.org/std/process/trait.Termination.html
+/// [`Fatal::new`]: struct.Fatal.html#method.new
+/// [`run`]: fn.run.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// use die::{codes, Fatal};
+/// use std::process::ExitCode;
+///
+/// fn real_main() -> Result<(), Fatal> {
+///     let port: u16 = "http".parse()?; // dies with `invalid digit found in string` and code 1
+///     let _ = port;
+///     Err(Fatal::new(codes::EX_CONFIG, "no listen address")) // would die with code 78
+/// }
+///
+/// fn main() -> ExitCode {
+///     die::run(real_main)
+/// }
+/// ```
+pub struct Fatal {
+    code: i32,
+    message: Option<String>,
+}
+
+impl Fatal {
+    /// Creates a `Fatal` that will print `message` and exit with `code`
+    pub fn new(code: impl IntoExitCode, message: impl fmt::Display) -> Fatal {
+        Fatal {
+            code: code.into_exit_code(),
+            message: Some(message.to_string()),
+        }
+    }
+
+    /// Creates a `Fatal` that will exit with `code` without printing anything
+    pub fn code_only(code: impl IntoExitCode) -> Fatal {
+        Fatal {
+            code: code.into_exit_code(),
+            message: None,
+        }
+    }
+
+    /// The code the process will exit with
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// The message that will be printed, or `None` if there is only an exit code
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Dies with this message and exit code, exactly like [`die`]!() would
+    ///
+    /// [`die`]: macro.die.html
+    #[track_caller]
+    pub fn exit(self) -> ! {
+        match self.message {
+            Some(message) => print_exit(self.code, message),
+            None => ::PrintExit::print_exit(&self.code),
+        }
+    }
+}
+
+impl<E: Error + 'static> From<E> for Fatal {
+    fn from(err: E) -> Fatal {
+        match broken_pipe::code(&err) {
+            Some(code) => Fatal::code_only(code),
+            None => Fatal::new(default_exit_code(), Chain(&err)),
+        }
+    }
+}
+
+impl fmt::Display for Fatal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.message {
+            Some(ref message) => f.write_str(message),
+            None => write!(f, "exit code {}", self.code),
+        }
+    }
+}
+
+/// Prints only the message, so std's `Error: ` line for a `Result<(), Fatal>` returned from
+/// `main` stays readable
+impl fmt::Debug for Fatal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl Termination for Fatal {
+    fn report(self) -> ExitCode {
+        self.exit()
+    }
+}
+
+/// Runs `f`, dying with the message and code of the [`Fatal`] it returns, if any
+///
+/// Meant to wrap the body of `main` so it can use `?` while keeping control of the exit code.
+///
+/// [`Fatal`]: struct.Fatal.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// use die::Fatal;
+/// use std::process::ExitCode;
+///
+/// fn main() -> ExitCode {
+///     die::run(|| {
+///         std::fs::read("/nonexistent/config")?; // prints `No such file or directory (os error 2)` then exits with code 1
+///         Ok(())
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn run(f: impl FnOnce() -> Result<(), Fatal>) -> ExitCode {
+    match f() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(fatal) => fatal.exit(),
+    }
+}
@@ -0,0 +1,88 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::panic::Location;
+
+use {exit, unwind, DieInfo, IntoExitCode};
+
+/// A guard that dies with its message and exit code when dropped, unless [`disarm`]ed first
+///
+/// For code paths that must complete or the process must not continue: create the guard before
+/// and disarm it after, any early return or `?` in between dies. The location reported, like with
+/// [`set_location`], is where the guard was created.
+///
+/// With the `panic` feature, a guard dropped while its thread is already unwinding does nothing,
+/// so the original panic, or [`Died`], carries on.
+///
+/// [`disarm`]: #method.disarm
+/// [`set_location`]: fn.set_location.html
+/// [`Died`]: struct.Died.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// use die::DieGuard;
+///
+/// fn migrate() -> Result<(), std::io::Error> {
+///     let guard = DieGuard::new(70, "migration did not complete");
+///     std::fs::read("/nonexistent/migration.sql")?; // returns early, dropping the guard
+///     guard.disarm();
+///     Ok(())
+/// }
+///
+/// let _ = migrate(); // prints `migration did not complete` then exits with code 70
+/// ```
+#[must_use = "the guard dies as soon as it is dropped"]
+pub struct DieGuard {
+    code: i32,
+    message: String,
+    location: &'static Location<'static>,
+    armed: bool,
+}
+
+impl DieGuard {
+    /// Creates a guard that will print `message` and exit with `code` when dropped
+    #[track_caller]
+    pub fn new(code: impl IntoExitCode, message: impl fmt::Display) -> DieGuard {
+        DieGuard {
+            code: code.into_exit_code(),
+            message: message.to_string(),
+            location: Location::caller(),
+            armed: true,
+        }
+    }
+
+    /// Defuses the guard, dropping it without dying
+    pub fn disarm(mut self) {
+        self.armed = false;
+    }
+}
+
+impl fmt::Debug for DieGuard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DieGuard")
+            .field("code", &self.code)
+            .field("message", &self.message)
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+impl Drop for DieGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        // unwinding out of a drop that is already unwinding would abort
+        if unwind::unwinds() && ::std::thread::panicking() {
+            return;
+        }
+        let mut info = DieInfo::new(Some(&self.message), self.code);
+        info.location = self.location;
+        exit(info)
+    }
+}
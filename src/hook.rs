@@ -0,0 +1,132 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::cell::Cell;
+use std::sync::{PoisonError, RwLock};
+
+#[cfg(feature = "json")]
+use json;
+#[cfg(feature = "logfmt")]
+use logfmt;
+#[cfg(feature = "wrap")]
+use wrap;
+use {config, message, output, DieInfo};
+
+type Hook = Box<dyn Fn(&DieInfo) + Send + Sync + 'static>;
+
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+thread_local! {
+    static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Registers a custom die hook, replacing the previously registered hook
+///
+/// The die hook is invoked right before the process exits, with the message and exit code of
+/// every [`die`]!() and [`Die`] call, mirroring [`std::panic::set_hook`]. The default hook prints
+/// the message, if any, and the backtrace, if one was captured, to [`stderr`] or the writer set
+/// with [`set_output`]; a custom hook replaces that, so it is responsible for any output.
+///
+/// If a hook itself dies, the nested call falls back to the default hook instead of recursing.
+///
+/// [`stderr`] stays locked while the hook runs, so other threads can't print in the middle of the
+/// message, which also means a hook must not wait on other threads that write to it.
+///
+/// [`die`]: macro.die.html
+/// [`Die`]: trait.Die.html
+/// [`std::panic::set_hook`]: https://doc.rust-lang.org/std/panic/fn.set_hook.html
+/// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+/// [`set_output`]: fn.set_output.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_hook(Box::new(|info| {
+///     eprintln!("fatal ({}): {}", info.code(), info);
+/// }));
+/// die!(3; "no config"); // prints `fatal (3): no config` to stderr then exits with code 3
+/// ```
+pub fn set_hook(hook: Box<dyn Fn(&DieInfo) + Send + Sync + 'static>) {
+    let old = HOOK
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .replace(hook);
+    // don't run arbitrary Drop code while holding the lock
+    drop(old);
+}
+
+/// Unregisters the current die hook, returning it
+///
+/// The default hook is registered in its place, and returned if no custom hook was registered.
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_hook(Box::new(|_| eprintln!("custom")));
+/// let _ = die::take_hook();
+/// die!("default"); // prints `default` to stderr then exits with code 1
+/// ```
+pub fn take_hook() -> Box<dyn Fn(&DieInfo) + Send + Sync + 'static> {
+    HOOK.write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()
+        .unwrap_or_else(|| Box::new(default_hook))
+}
+
+fn default_hook(info: &DieInfo) {
+    if config::quiet() {
+        return;
+    }
+    output::with(info.stdout, |out, terminal| {
+        #[cfg(feature = "json")]
+        if config::json() {
+            let _ = json::write(out, info);
+            let _ = out.flush();
+            return;
+        }
+        #[cfg(feature = "logfmt")]
+        if config::logfmt() {
+            let _ = logfmt::write(out, info);
+            let _ = out.flush();
+            return;
+        }
+        if let Some(message) = info.message() {
+            let timestamp = message::TimestampPrefix::now();
+            #[cfg(feature = "wrap")]
+            let offset = timestamp.width() + message::error_label(false).len();
+            #[cfg(feature = "wrap")]
+            let message = wrap::wrap(message, offset, terminal);
+            let label = message::error_label(terminal);
+            let _ = writeln!(out, "{}{}{}", timestamp, label, message);
+        }
+        if let Some(backtrace) = info.backtrace() {
+            let _ = writeln!(out, "stack backtrace:\n{}", backtrace);
+        }
+        let _ = out.flush();
+    })
+}
+
+/// Runs the registered hook, or the default one if none is registered or we are already inside
+/// the hook on this thread
+pub(crate) fn call(info: &DieInfo) {
+    // cleared on drop, so a hook that dies or panics and is unwound out of doesn't stay flagged
+    struct InHook;
+    impl Drop for InHook {
+        fn drop(&mut self) {
+            IN_HOOK.with(|in_hook| in_hook.set(false));
+        }
+    }
+    if IN_HOOK.with(|in_hook| in_hook.replace(true)) {
+        return default_hook(info);
+    }
+    let _in_hook = InHook;
+    match *HOOK.read().unwrap_or_else(PoisonError::into_inner) {
+        Some(ref hook) => hook(info),
+        None => default_hook(info),
+    }
+}
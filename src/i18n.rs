@@ -0,0 +1,50 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+
+type Translator = Box<dyn Fn(&str) -> Option<String> + Send + Sync + 'static>;
+
+static TRANSLATOR: RwLock<Option<Translator>> = RwLock::new(None);
+
+/// Registers a message catalog lookup, replacing the previously registered one
+///
+/// Every message is passed to it before printing, and replaced by what it returns, so messages
+/// can be keys into a catalog, a Fluent bundle or a simple map picked by locale. Messages it
+/// returns `None` for are printed as they are. Only the message is translated, before the
+/// program prefix and location are added.
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// use std::collections::HashMap;
+/// let mut de = HashMap::new();
+/// de.insert("config-missing", "Konfigurationsdatei fehlt");
+/// let catalog = match std::env::var("LANG") {
+///     Ok(ref lang) if lang.starts_with("de") => de,
+///     _ => HashMap::new(),
+/// };
+/// die::set_translator(move |key| catalog.get(key).map(|m| m.to_string()));
+/// die!("config-missing"); // prints `Konfigurationsdatei fehlt` with LANG=de_DE.UTF-8, else `config-missing`, then exits with code 1
+/// ```
+pub fn set_translator(translator: impl Fn(&str) -> Option<String> + Send + Sync + 'static) {
+    let old = TRANSLATOR
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .replace(Box::new(translator));
+    // don't run arbitrary Drop code while holding the lock
+    drop(old);
+}
+
+/// The translation of `message`, if a translator is registered and knows it
+pub(crate) fn translate(message: &dyn fmt::Display) -> Option<String> {
+    match *TRANSLATOR.read().unwrap_or_else(PoisonError::into_inner) {
+        Some(ref translator) => translator(&message.to_string()),
+        None => None,
+    }
+}
@@ -0,0 +1,149 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::fmt;
+use core::panic::Location;
+#[cfg(feature = "std")]
+use std::any::Any;
+#[cfg(feature = "std")]
+use std::backtrace::Backtrace;
+
+/// A struct providing information about a call to [`die`]!() or the [`Die`] trait, passed to the
+/// hook registered with [`set_hook`]
+///
+/// [`die`]: macro.die.html
+/// [`Die`]: trait.Die.html
+/// [`set_hook`]: fn.set_hook.html
+pub struct DieInfo<'a> {
+    pub(crate) message: Option<&'a dyn fmt::Display>,
+    pub(crate) code: i32,
+    pub(crate) location: &'a Location<'a>,
+    pub(crate) stdout: bool,
+    pub(crate) abort: bool,
+    pub(crate) signal: Option<i32>,
+    #[cfg(feature = "std")]
+    pub(crate) backtrace: Option<Backtrace>,
+    #[cfg(feature = "std")]
+    pub(crate) payload: Option<Box<dyn Any + Send>>,
+}
+
+impl<'a> DieInfo<'a> {
+    #[track_caller]
+    pub(crate) fn new(message: Option<&'a dyn fmt::Display>, code: i32) -> DieInfo<'a> {
+        DieInfo {
+            message,
+            code,
+            location: Location::caller(),
+            stdout: false,
+            abort: false,
+            signal: None,
+            #[cfg(feature = "std")]
+            backtrace: None,
+            #[cfg(feature = "std")]
+            payload: None,
+        }
+    }
+
+    /// The message that will be printed, or `None` if the process is exiting with only a code,
+    /// like `die!(2)`
+    pub fn message(&self) -> Option<&dyn fmt::Display> {
+        self.message
+    }
+
+    /// The code the process is about to exit with
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// The code the process is about to exit with as the `u32` Windows sees, like `0xC000013A`
+    pub fn code_u32(&self) -> u32 {
+        self.code as u32
+    }
+
+    /// The source location of the [`die`]!() or [`Die`] call that is dying
+    ///
+    /// [`die`]: macro.die.html
+    /// [`Die`]: trait.Die.html
+    pub fn location(&self) -> &Location<'a> {
+        self.location
+    }
+
+    /// Whether the message is meant for stdout instead of stderr, like from [`die_out`]!()
+    ///
+    /// [`die_out`]: macro.die_out.html
+    pub fn stdout(&self) -> bool {
+        self.stdout
+    }
+
+    /// Whether the process is about to abort instead of exiting, like from [`die_abort`]!()
+    ///
+    /// [`die_abort`]: macro.die_abort.html
+    pub fn abort(&self) -> bool {
+        self.abort
+    }
+
+    /// The signal the process is about to raise instead of exiting, like from [`die_signal`]!()
+    ///
+    /// [`die_signal`]: macro.die_signal.html
+    pub fn signal(&self) -> Option<i32> {
+        self.signal
+    }
+
+    /// The backtrace of where the process is dying, only captured when enabled with
+    /// [`set_backtrace`] and by the `RUST_BACKTRACE` environment variable
+    ///
+    /// [`set_backtrace`]: fn.set_backtrace.html
+    #[cfg(feature = "std")]
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace.as_ref()
+    }
+
+    /// The payload attached with [`die_with_payload`] or `die!(payload = ...)`, to
+    /// [`downcast_ref`] to what was passed
+    ///
+    /// [`die_with_payload`]: fn.die_with_payload.html
+    /// [`downcast_ref`]: https://doc.rust-lang.org/std/any/trait.Any.html#method.downcast_ref
+    #[cfg(feature = "std")]
+    pub fn payload(&self) -> Option<&(dyn Any + Send)> {
+        self.payload.as_deref()
+    }
+}
+
+impl fmt::Display for DieInfo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.message {
+            Some(message) => message.fmt(f),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for DieInfo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug = f.debug_struct("DieInfo");
+        debug
+            .field("message", &self.message.map(Quoted))
+            .field("code", &self.code)
+            .field("location", &self.location)
+            .field("stdout", &self.stdout)
+            .field("abort", &self.abort)
+            .field("signal", &self.signal);
+        #[cfg(feature = "std")]
+        debug.field("backtrace", &self.backtrace);
+        #[cfg(feature = "std")]
+        debug.field("payload", &self.payload);
+        debug.finish()
+    }
+}
+
+/// Debugs a message like the `String` it would print as
+struct Quoted<'a>(&'a dyn fmt::Display);
+
+impl fmt::Debug for Quoted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", self.0)
+    }
+}
@@ -0,0 +1,60 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `die!(err)` for [`anyhow::Error`], printing the whole context chain like `{:#}` does
+//!
+//! [`anyhow::Error`]: https://docs.rs/anyhow/1/anyhow/struct.Error.html
+//!
+//! ```should_panic
+//! # extern crate anyhow;
+//! # extern crate die;
+//! use anyhow::Context;
+//! use die::DieResult;
+//!
+//! let res = Err::<(), _>(anyhow::anyhow!("no such file")).context("cannot read config");
+//! res.die_err("startup failed"); // prints `startup failed: cannot read config: no such file` then exits with code 1
+//! ```
+//!
+//! ```should_panic
+//! # #[macro_use] extern crate anyhow;
+//! # #[macro_use] extern crate die;
+//! # fn main() {
+//! let err = anyhow!("no such file").context("cannot read config");
+//! die!(err; 3); // prints `cannot read config: no such file` then exits with code 3
+//! # }
+//! ```
+
+use anyhow::Error;
+
+use exit_code::DefaultExitCode;
+use {broken_pipe, print_exit, IntoExitCode, PrintExit};
+
+impl PrintExit for Error {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        broken_pipe::check(self.as_ref());
+        print_exit(DefaultExitCode, format_args!("{:#}", self))
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (C, Error) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        broken_pipe::check(self.1.as_ref());
+        print_exit(self.0, format_args!("{:#}", self.1))
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (Error, C) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        broken_pipe::check(self.0.as_ref());
+        print_exit(self.1, format_args!("{:#}", self.0))
+    }
+}
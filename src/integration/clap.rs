@@ -0,0 +1,79 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `die!(err)` for [`clap::Error`], printed by clap itself and exiting with clap's code
+//!
+//! clap formats its own errors, with colors, usage and suggestions, and prints `--help` and
+//! `--version` to stdout, so that is left to it. The exit code is clap's too. Usage errors die
+//! with `2`, and the die hook and integrations only see the exit code. `--help` and `--version`
+//! aren't failures, so they exit with `0` right after clap prints them, without the hook, the
+//! integrations or the logs hearing about it.
+//!
+//! [`clap::Error`]: https://docs.rs/clap/4/clap/type.Error.html
+//!
+//! ```should_panic
+//! # extern crate clap;
+//! # extern crate die;
+//! use die::DieClap;
+//!
+//! let matches = clap::Command::new("myprog")
+//!     .arg(clap::Arg::new("file").required(true))
+//!     .try_get_matches_from(["myprog"])
+//!     .die_clap(); // prints clap's error and usage to stderr then exits with code 2
+//! ```
+
+use std::io::{self, Write};
+use std::process;
+
+use clap::Error;
+
+use {exit, unwind, DieInfo, PrintExit};
+
+impl PrintExit for Error {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        let _ = self.print();
+        if self.use_stderr() {
+            exit(DieInfo::new(None, self.exit_code()))
+        }
+        let _ = io::stdout().flush();
+        if unwind::unwinds() {
+            unwind::unwind(&mut DieInfo::new(None, self.exit_code()))
+        }
+        process::exit(self.exit_code())
+    }
+}
+
+/// `DieClap` is a trait implemented on the [`Result`]s of clap, to exit the way clap does
+///
+/// [`Die`] works on them too, but prints its own message instead of clap's.
+///
+/// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+/// [`Die`]: trait.Die.html
+pub trait DieClap<T> {
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`]
+    ///
+    /// # Exits
+    ///
+    /// If the value is an [`Err`], clap prints it, then the process exits with clap's exit code.
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    fn die_clap(self) -> T;
+}
+
+impl<T> DieClap<T> for Result<T, Error> {
+    #[inline]
+    #[track_caller]
+    fn die_clap(self) -> T {
+        match self {
+            Ok(t) => t,
+            Err(err) => err.print_exit(),
+        }
+    }
+}
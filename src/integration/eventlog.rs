@@ -0,0 +1,67 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Records every die as an error event in the Windows Event Log, so services and scheduled tasks,
+//! whose [`stderr`] nobody reads, leave a trace operators can find in the Event Viewer
+//!
+//! Events are recorded in the Application log under the source set by [`set_event_source`], or
+//! the program name, with the exit code as event ID. Without a message file registered for the
+//! source the Event Viewer says the description can't be found, then shows the message anyway. A
+//! die with only an exit code records `exiting with code N`.
+//!
+//! [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+//! [`set_event_source`]: ../fn.set_event_source.html
+
+use std::ffi::c_void;
+use std::ptr;
+
+use super::wide;
+use {config, DieInfo};
+
+const EVENTLOG_ERROR_TYPE: u16 = 1;
+
+#[link(name = "advapi32")]
+extern "system" {
+    fn RegisterEventSourceW(server: *const u16, source: *const u16) -> *mut c_void;
+    fn ReportEventW(
+        log: *mut c_void,
+        kind: u16,
+        category: u16,
+        event_id: u32,
+        sid: *mut c_void,
+        num_strings: u16,
+        data_size: u32,
+        strings: *const *const u16,
+        data: *mut c_void,
+    ) -> i32;
+    fn DeregisterEventSource(log: *mut c_void) -> i32;
+}
+
+pub(crate) fn before_exit(info: &DieInfo) {
+    let text = match info.message() {
+        Some(message) => message.to_string(),
+        None => format!("exiting with code {}", info.code()),
+    };
+    let (source, text) = (wide(&config::event_source()), wide(&text));
+    unsafe {
+        let log = RegisterEventSourceW(ptr::null(), source.as_ptr());
+        if log.is_null() {
+            return;
+        }
+        ReportEventW(
+            log,
+            EVENTLOG_ERROR_TYPE,
+            0,
+            info.code() as u32,
+            ptr::null_mut(),
+            1,
+            0,
+            &text.as_ptr(),
+            ptr::null_mut(),
+        );
+        DeregisterEventSource(log);
+    }
+}
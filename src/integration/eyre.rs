@@ -0,0 +1,65 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `die!(report)` for [`eyre::Report`], rendered through the installed report handler
+//!
+//! The report is printed with `{:?}`, which is how eyre renders a report through its handler, so
+//! a handler installed by e.g. `color_eyre::install()` produces its usual pretty output.
+//! [`DieResult::die_debug`] does the same for a `Result<T, eyre::Report>`.
+//!
+//! [`eyre::Report`]: https://docs.rs/eyre/0.6/eyre/struct.Report.html
+//! [`DieResult::die_debug`]: ../../trait.DieResult.html#tymethod.die_debug
+//!
+//! ```should_panic
+//! # #[macro_use] extern crate die;
+//! # extern crate eyre;
+//! # fn main() {
+//! let report = eyre::eyre!("no such file").wrap_err("cannot read config");
+//! die!(report; 3); // prints the report then exits with code 3
+//! # }
+//! ```
+//!
+//! ```should_panic
+//! # extern crate die;
+//! # extern crate eyre;
+//! use die::DieResult;
+//! use eyre::WrapErr;
+//!
+//! let res = Err::<(), _>(eyre::eyre!("no such file")).wrap_err("cannot read config");
+//! res.die_debug("startup failed"); // prints `startup failed: ` followed by the report then exits with code 1
+//! ```
+
+use eyre::Report;
+
+use exit_code::DefaultExitCode;
+use {broken_pipe, print_exit, IntoExitCode, PrintExit};
+
+impl PrintExit for Report {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        broken_pipe::check(self.as_ref());
+        print_exit(DefaultExitCode, format_args!("{:?}", self))
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (C, Report) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        broken_pipe::check(self.1.as_ref());
+        print_exit(self.0, format_args!("{:?}", self.1))
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (Report, C) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        broken_pipe::check(self.0.as_ref());
+        print_exit(self.1, format_args!("{:?}", self.0))
+    }
+}
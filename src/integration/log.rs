@@ -0,0 +1,26 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sends every die message through [`log::error!`] and flushes the logger before exiting, so
+//! file and syslog appenders receive the final message that [`process::exit`] would otherwise
+//! drop
+//!
+//! Records use the `die` target. This is in addition to the die hook, which still prints to
+//! [`stderr`] by default.
+//!
+//! [`log::error!`]: https://docs.rs/log/0.4/log/macro.error.html
+//! [`process::exit`]: https://doc.rust-lang.org/std/process/fn.exit.html
+//! [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+
+use DieInfo;
+
+pub(crate) fn before_exit(info: &DieInfo) {
+    match info.message() {
+        Some(message) => ::log::error!(target: "die", "{}", message),
+        None => ::log::error!(target: "die", "exiting with code {}", info.code()),
+    }
+    ::log::logger().flush();
+}
@@ -0,0 +1,42 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Mirrors every die message to logcat with priority `ERROR`, as [`stderr`] of an Android app
+//! usually goes nowhere and fatal errors would vanish
+//!
+//! Messages are tagged with the tag set by [`set_logcat_tag`], or the program name. A die with
+//! only an exit code logs `exiting with code N`.
+//!
+//! [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+//! [`set_logcat_tag`]: ../fn.set_logcat_tag.html
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+
+use {config, DieInfo};
+
+const ANDROID_LOG_ERROR: c_int = 6;
+
+#[link(name = "log")]
+extern "C" {
+    fn __android_log_write(prio: c_int, tag: *const c_char, text: *const c_char) -> c_int;
+}
+
+/// `s` as a C string, with the NULs it can't hold escaped like syslog does
+fn c_string(s: &str) -> CString {
+    CString::new(s.replace('\0', "\\0")).expect("NULs were escaped")
+}
+
+pub(crate) fn before_exit(info: &DieInfo) {
+    let text = match info.message() {
+        Some(message) => message.to_string(),
+        None => format!("exiting with code {}", info.code()),
+    };
+    let (tag, text) = (c_string(&config::logcat_tag()), c_string(&text));
+    unsafe {
+        __android_log_write(ANDROID_LOG_ERROR, tag.as_ptr(), text.as_ptr());
+    }
+}
@@ -0,0 +1,82 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `die!(report)` for [`miette::Report`], rendering the full fancy diagnostic
+//!
+//! The report is printed with `{:?}`, which renders it through miette's installed report
+//! handler, including severity, labels, source snippets and help. Wrap any [`Diagnostic`] with
+//! [`Report::new`] to die with it; [`DieResult::die_debug`] does the same for a
+//! `miette::Result<T>`.
+//!
+//! Like other errors, a report exits quietly on a broken pipe if enabled with
+//! [`set_broken_pipe`] when an [`io::Error`] is among its sources. One turned into a report with
+//! `into_diagnostic` isn't found, as miette hides it.
+//!
+//! [`miette::Report`]: https://docs.rs/miette/7/miette/struct.Report.html
+//! [`Diagnostic`]: https://docs.rs/miette/7/miette/trait.Diagnostic.html
+//! [`Report::new`]: https://docs.rs/miette/7/miette/struct.Report.html#method.new
+//! [`DieResult::die_debug`]: ../../trait.DieResult.html#tymethod.die_debug
+//! [`set_broken_pipe`]: ../../fn.set_broken_pipe.html
+//! [`io::Error`]: https://doc.rust-lang.org/std/io/struct.Error.html
+//!
+//! ```should_panic
+//! # #[macro_use] extern crate die;
+//! # extern crate miette;
+//! # fn main() {
+//! let report = miette::miette!(
+//!     code = "config::parse",
+//!     help = "quote the value",
+//!     "unexpected character"
+//! );
+//! die!(report; 65); // prints the rendered diagnostic then exits with code 65
+//! # }
+//! ```
+
+use std::io;
+
+use miette::Report;
+
+use exit_code::DefaultExitCode;
+use {broken_pipe, print_exit, IntoExitCode, PrintExit};
+
+/// Exits quietly if `report` is or has a broken pipe among its sources, like [`broken_pipe::check`]
+#[inline]
+#[track_caller]
+fn check(report: &Report) {
+    if let Some(err) = report.downcast_ref::<io::Error>() {
+        broken_pipe::check(err);
+    }
+    if let Some(err) = report.chain().next() {
+        broken_pipe::check(err);
+    }
+}
+
+impl PrintExit for Report {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        check(self);
+        print_exit(DefaultExitCode, format_args!("{:?}", self))
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (C, Report) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        check(&self.1);
+        print_exit(self.0, format_args!("{:?}", self.1))
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (Report, C) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        check(&self.0);
+        print_exit(self.1, format_args!("{:?}", self.0))
+    }
+}
@@ -0,0 +1,76 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Optional integrations with other crates, each behind a feature of the same name
+
+use DieInfo;
+
+#[cfg(feature = "anyhow")]
+mod anyhow;
+#[cfg(feature = "clap")]
+mod clap;
+#[cfg(all(feature = "eventlog", windows))]
+mod eventlog;
+#[cfg(feature = "eyre")]
+mod eyre;
+#[cfg(feature = "log")]
+mod log;
+#[cfg(all(feature = "logcat", target_os = "android"))]
+mod logcat;
+#[cfg(feature = "miette")]
+mod miette;
+#[cfg(feature = "sentry")]
+mod sentry;
+#[cfg(feature = "serde_json")]
+mod serde_json;
+#[cfg(feature = "statsd")]
+mod statsd;
+#[cfg(all(feature = "syslog", unix))]
+mod syslog;
+#[cfg(feature = "tracing")]
+mod tracing;
+#[cfg(all(feature = "wasm", target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) mod wasm;
+#[cfg(all(feature = "windows-gui", windows))]
+mod windows_gui;
+
+#[cfg(feature = "clap")]
+pub use self::clap::DieClap;
+#[cfg(feature = "serde_json")]
+pub use self::serde_json::DieJson;
+#[cfg(feature = "statsd")]
+pub use self::statsd::set_statsd;
+#[cfg(feature = "tracing")]
+pub use self::tracing::drop_on_exit;
+
+/// `s` as the NUL terminated UTF-16 Windows takes
+#[cfg(all(any(feature = "windows-gui", feature = "eventlog"), windows))]
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+/// Passes a die about to happen to every enabled integration, after the die hook has run
+#[allow(unused_variables)]
+pub(crate) fn before_exit(info: &DieInfo) {
+    #[cfg(feature = "log")]
+    log::before_exit(info);
+    #[cfg(feature = "tracing")]
+    tracing::before_exit(info);
+    #[cfg(all(feature = "syslog", unix))]
+    syslog::before_exit(info);
+    #[cfg(all(feature = "logcat", target_os = "android"))]
+    logcat::before_exit(info);
+    #[cfg(all(feature = "eventlog", windows))]
+    eventlog::before_exit(info);
+    #[cfg(feature = "sentry")]
+    sentry::before_exit(info);
+    #[cfg(feature = "statsd")]
+    statsd::before_exit(info);
+    #[cfg(all(feature = "wasm", target_arch = "wasm32", target_os = "unknown"))]
+    wasm::before_exit(info);
+    #[cfg(all(feature = "windows-gui", windows))]
+    windows_gui::before_exit(info);
+}
@@ -0,0 +1,44 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Captures every die as a `fatal` Sentry event, tagged with `exit_code`, then blocks for up to
+//! 2 seconds for it to be sent
+//!
+//! The event goes to the client bound to the current hub, as set up by `sentry::init`, and
+//! nothing happens if there is none. Set `attach_stacktrace` in the client options to include a
+//! stack trace of where the process died.
+
+use std::time::Duration;
+
+use sentry_core::protocol::{Event, Level};
+use sentry_core::Hub;
+
+use DieInfo;
+
+/// How long to block waiting for the event to be sent before exiting anyway
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub(crate) fn before_exit(info: &DieInfo) {
+    let hub = Hub::current();
+    let client = match hub.client() {
+        Some(client) => client,
+        None => return,
+    };
+    let mut event = Event {
+        level: Level::Fatal,
+        message: Some(match info.message() {
+            Some(message) => message.to_string(),
+            None => format!("exiting with code {}", info.code()),
+        }),
+        logger: Some("die".to_string()),
+        ..Default::default()
+    };
+    event
+        .tags
+        .insert("exit_code".to_string(), info.code().to_string());
+    hub.capture_event(event);
+    client.flush(Some(FLUSH_TIMEOUT));
+}
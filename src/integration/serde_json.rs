@@ -0,0 +1,170 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `die!(err)` for [`serde_json::Error`], saying whether it's a syntax or a data error
+//!
+//! The error is printed after its category, `syntax error: `, `data error: ` or `I/O error: `,
+//! and serde_json already ends it with the line and column. [`DieJson`] also prints the offending
+//! line of the input with a caret under the column.
+//!
+//! [`serde_json::Error`]: https://docs.rs/serde_json/1/serde_json/struct.Error.html
+//! [`DieJson`]: ../../trait.DieJson.html
+//!
+//! ```should_panic
+//! # #[macro_use] extern crate die;
+//! # extern crate serde_json;
+//! # fn main() {
+//! let err = serde_json::from_str::<u16>("\"8080\"").unwrap_err();
+//! die!(err; 65); // prints `data error: invalid type: string "8080", expected u16 at line 1 column 6` then exits with code 65
+//! # }
+//! ```
+
+use std::fmt;
+
+use serde_json::error::Category;
+use serde_json::Error;
+
+use exit_code::DefaultExitCode;
+use {broken_pipe, print_exit, snippet, IntoExitCode, PrintExit};
+
+/// `DieJson` is a trait implemented on the [`Result`]s of serde_json, to print the offending line
+/// of the input that failed to parse along with the error
+///
+/// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+pub trait DieJson<T> {
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(1)][exit] if the value is an [`Err`], after printing the category
+    /// and the error, then the line of `input` it happened on with a caret under the column, to
+    /// [`stderr`].
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # extern crate die;
+    /// # extern crate serde_json;
+    /// use die::DieJson;
+    ///
+    /// let input = "{\n  \"port\" 8080\n}";
+    /// let config: serde_json::Value = serde_json::from_str(input).die_json(input);
+    /// // prints
+    /// // syntax error: expected `:` at line 2 column 10
+    /// //   |
+    /// // 2 |   "port" 8080
+    /// //   |          ^
+    /// // to stderr then exits with code 1
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    fn die_json(self, input: &str) -> T;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the value is an [`Err`], after printing the
+    /// category and the error, then the line of `input` it happened on with a caret under the
+    /// column, to [`stderr`].
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # extern crate die;
+    /// # extern crate serde_json;
+    /// use die::DieJson;
+    ///
+    /// let input = "[1, 2,]";
+    /// let list: Vec<u32> = serde_json::from_str(input).die_json_code(input, 65); // prints `syntax error: trailing comma at line 1 column 7` and the line then exits with code 65
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    fn die_json_code(self, input: &str, exit_code: impl IntoExitCode) -> T;
+}
+
+impl<T> DieJson<T> for Result<T, Error> {
+    #[inline]
+    #[track_caller]
+    fn die_json(self, input: &str) -> T {
+        self.die_json_code(input, DefaultExitCode)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn die_json_code(self, input: &str, exit_code: impl IntoExitCode) -> T {
+        match self {
+            Ok(t) => t,
+            Err(err) => {
+                broken_pipe::check(&err);
+                print_exit(exit_code, Json(&err, Some(input)))
+            }
+        }
+    }
+}
+
+impl PrintExit for Error {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        broken_pipe::check(self);
+        print_exit(DefaultExitCode, Json(self, None))
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (C, Error) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        broken_pipe::check(&self.1);
+        print_exit(self.0, Json(&self.1, None))
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (Error, C) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        broken_pipe::check(&self.0);
+        print_exit(self.1, Json(&self.0, None))
+    }
+}
+
+/// A serde_json error after its category, followed by the offending line of the input if given
+struct Json<'a>(&'a Error, Option<&'a str>);
+
+impl fmt::Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let err = self.0;
+        let category = match err.classify() {
+            Category::Io => "I/O",
+            Category::Syntax | Category::Eof => "syntax",
+            Category::Data => "data",
+        };
+        write!(f, "{} error: {}", category, err)?;
+        let line = match self.1 {
+            Some(input) if err.line() > 0 => match input.lines().nth(err.line() - 1) {
+                Some(line) => line,
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+        // serde_json columns count bytes from 1, and point at the offending byte
+        let mut byte = err.column().saturating_sub(1).min(line.len());
+        while !line.is_char_boundary(byte) {
+            byte -= 1;
+        }
+        snippet::excerpt(f, err.line(), line, line[..byte].chars().count() + 1)
+    }
+}
@@ -0,0 +1,59 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Increments a statsd counter tagged with the exit code for every die, so fleets can alert on
+//! the rate of fatal exits of CLI jobs and sidecars
+//!
+//! The counter is sent as a single UDP datagram in the DogStatsD tag format, like
+//! `myprog.fatal:1|c|#code:3`, which Datadog, Telegraf and the Prometheus statsd exporter all
+//! understand. UDP never waits for the collector, and failing to send is ignored.
+
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::sync::{PoisonError, RwLock};
+
+use DieInfo;
+
+static TARGET: RwLock<Option<(String, String)>> = RwLock::new(None);
+
+/// Sends the counter `metric`, tagged with the exit code, to the statsd server at `addr` for every
+/// die, with the `statsd` feature
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_statsd("127.0.0.1:8125", "myprog.fatal");
+/// die!(3; "no such file"); // prints `no such file`, sends `myprog.fatal:1|c|#code:3`, then exits with code 3
+/// ```
+pub fn set_statsd(addr: impl Into<String>, metric: impl Into<String>) {
+    *TARGET.write().unwrap_or_else(PoisonError::into_inner) = Some((addr.into(), metric.into()));
+}
+
+pub(crate) fn before_exit(info: &DieInfo) {
+    let target = TARGET.read().unwrap_or_else(PoisonError::into_inner);
+    let (addr, metric) = match *target {
+        Some((ref addr, ref metric)) => (addr, metric),
+        None => return,
+    };
+    let addr = match addr
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+    {
+        Some(addr) => addr,
+        None => return,
+    };
+    let datagram = format!("{}:1|c|#code:{}", metric, info.code());
+    // the socket has to be of the family the name resolved to, an IPv6 only host included
+    let bound = if addr.is_ipv6() {
+        UdpSocket::bind("[::]:0")
+    } else {
+        UdpSocket::bind("0.0.0.0:0")
+    };
+    if let Ok(socket) = bound {
+        let _ = socket.send_to(datagram.as_bytes(), addr);
+    }
+}
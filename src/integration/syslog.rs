@@ -0,0 +1,64 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Mirrors every die message to syslog with priority `LOG_ERR`, which systemd also forwards to
+//! the journal, so daemons leave a trace even when [`stderr`] isn't captured
+//!
+//! Messages are logged with the ident set by a previous `openlog` call, or the program name if
+//! there was none. A die with only an exit code logs `exiting with code N`.
+//!
+//! [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+
+use std::fmt::{self, Write};
+
+use libc;
+
+use DieInfo;
+
+/// Longer messages are truncated, syslog daemons cut them off around there anyway
+const MAX_LEN: usize = 1024;
+
+/// A NUL terminated message built on the stack, so logging it doesn't allocate
+struct Buf {
+    bytes: [u8; MAX_LEN],
+    len: usize,
+}
+
+impl fmt::Write for Buf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            let escaped: &[u8] = if byte == 0 { b"\\0" } else { &[byte] };
+            for &byte in escaped {
+                // keep the last byte for the NUL
+                if self.len == MAX_LEN - 1 {
+                    return Err(fmt::Error);
+                }
+                self.bytes[self.len] = byte;
+                self.len += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn before_exit(info: &DieInfo) {
+    let mut message = Buf {
+        bytes: [0; MAX_LEN],
+        len: 0,
+    };
+    // a truncated message is still logged
+    let _ = match info.message() {
+        Some(text) => write!(message, "{}", text),
+        None => write!(message, "exiting with code {}", info.code()),
+    };
+    unsafe {
+        libc::syslog(
+            libc::LOG_ERR,
+            b"%s\0".as_ptr() as *const libc::c_char,
+            message.bytes.as_ptr() as *const libc::c_char,
+        );
+    }
+}
@@ -0,0 +1,66 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Emits every die as a [`tracing`] `ERROR` event carrying the exit code, then drops the guards
+//! registered with [`drop_on_exit`] so non-blocking writers flush it
+//!
+//! Events use the `die` target. This is in addition to the die hook, which still prints to
+//! [`stderr`] by default.
+//!
+//! [`tracing`]: https://docs.rs/tracing/0.1/tracing/
+//! [`drop_on_exit`]: ../../fn.drop_on_exit.html
+//! [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+
+use std::any::Any;
+use std::mem;
+use std::sync::{Mutex, PoisonError};
+
+use tracing::Level;
+
+use DieInfo;
+
+static GUARDS: Mutex<Vec<Box<dyn Any + Send>>> = Mutex::new(Vec::new());
+
+/// Keeps `guard` alive until the process dies, then drops it right before exiting
+///
+/// [`process::exit`] skips destructors, so the `WorkerGuard` of a non-blocking
+/// `tracing_appender` writer never flushes, losing the last, most important, events. Hand the
+/// guard over here instead of holding it in `main` and it is dropped after the fatal event is
+/// emitted. Guards are dropped in reverse order of registration.
+///
+/// [`process::exit`]: https://doc.rust-lang.org/std/process/fn.exit.html
+///
+/// # Examples
+///
+/// ```
+/// # struct WorkerGuard;
+/// # fn non_blocking() -> WorkerGuard { WorkerGuard }
+/// let guard = non_blocking();
+/// die::drop_on_exit(guard);
+/// ```
+pub fn drop_on_exit<G: Send + 'static>(guard: G) {
+    GUARDS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Box::new(guard));
+}
+
+pub(crate) fn before_exit(info: &DieInfo) {
+    match info.message() {
+        Some(message) => {
+            ::tracing::event!(target: "die", Level::ERROR, code = info.code(), "{}", message)
+        }
+        None => ::tracing::event!(
+            target: "die",
+            Level::ERROR,
+            code = info.code(),
+            "exiting with code {}",
+            info.code()
+        ),
+    }
+    let guards = mem::take(&mut *GUARDS.lock().unwrap_or_else(PoisonError::into_inner));
+    guards.into_iter().rev().for_each(drop);
+}
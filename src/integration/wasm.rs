@@ -0,0 +1,29 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! On `wasm32-unknown-unknown` [`stderr`] goes nowhere and [`process::exit`] isn't supported, so
+//! every die message is logged with `console.error` and then, instead of exiting, a JS exception
+//! is thrown, unwinding out of the wasm module like an exit would
+//!
+//! WASI needs none of this, [`process::exit`] already calls `proc_exit` there.
+//!
+//! [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+//! [`process::exit`]: https://doc.rust-lang.org/std/process/fn.exit.html
+
+use wasm_bindgen::{self, JsValue};
+use web_sys::console;
+
+use DieInfo;
+
+pub(crate) fn before_exit(info: &DieInfo) {
+    if let Some(message) = info.message() {
+        console::error_1(&JsValue::from_str(&message.to_string()));
+    }
+}
+
+pub(crate) fn exit(info: &DieInfo) -> ! {
+    wasm_bindgen::throw_str(&format!("exited with code {}", info.code()))
+}
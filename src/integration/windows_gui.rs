@@ -0,0 +1,51 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Shows every die message in an error message box too when the process has no console, as a
+//! `#![windows_subsystem = "windows"]` binary doesn't, so it would otherwise die without a word
+//!
+//! The box is titled with the program name and blocks until dismissed. A die with only an exit
+//! code, or with messages silenced by [`set_quiet`], shows nothing.
+//!
+//! [`set_quiet`]: ../fn.set_quiet.html
+
+use std::ffi::c_void;
+use std::ptr;
+
+use super::wide;
+use {config, DieInfo};
+
+const MB_ICONERROR: u32 = 0x10;
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetConsoleWindow() -> *mut c_void;
+}
+
+#[link(name = "user32")]
+extern "system" {
+    fn MessageBoxW(hwnd: *mut c_void, text: *const u16, caption: *const u16, kind: u32) -> i32;
+}
+
+pub(crate) fn before_exit(info: &DieInfo) {
+    let message = match info.message() {
+        Some(message) if !config::quiet() => message,
+        _ => return,
+    };
+    if !unsafe { GetConsoleWindow() }.is_null() {
+        return;
+    }
+    let text = wide(&message.to_string());
+    let caption = wide(&config::program_name().unwrap_or_else(|| "Error".to_string()));
+    unsafe {
+        MessageBoxW(
+            ptr::null_mut(),
+            text.as_ptr(),
+            caption.as_ptr(),
+            MB_ICONERROR,
+        );
+    }
+}
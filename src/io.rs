@@ -0,0 +1,89 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::io;
+
+use {codes, default_exit_code, print_exit_err};
+
+/// `DieIo` is a trait implemented on [`io::Result`], to exit with a code derived from the
+/// [`io::Error`], like systems tools that exit with the errno of what failed
+///
+/// [`io::Result`]: https://doc.rust-lang.org/std/io/type.Result.html
+/// [`io::Error`]: https://doc.rust-lang.org/std/io/struct.Error.html
+pub trait DieIo<T> {
+    /// Unwraps an [`io::Result`], yielding the content of an [`Ok`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(errno)][exit] with the [`raw_os_error`] of an [`Err`], or the default
+    /// exit code if it didn't come from the OS, after printing the passed message followed by `: `
+    /// and the error, OS error text included, to [`stderr`].
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use die::DieIo;
+    /// std::fs::read("/nonexistent").die_errno("cannot read config"); // prints `cannot read config: No such file or directory (os error 2)` to stderr then exits with code 2
+    /// ```
+    ///
+    /// [`io::Result`]: https://doc.rust-lang.org/std/io/type.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`raw_os_error`]: https://doc.rust-lang.org/std/io/struct.Error.html#method.raw_os_error
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    fn die_errno(self, msg: impl fmt::Display) -> T;
+
+    /// Unwraps an [`io::Result`], yielding the content of an [`Ok`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(code)][exit] with the sysexits code for the [`ErrorKind`] of an
+    /// [`Err`], see [`codes::from_io_error_kind`], after printing the passed message followed by
+    /// `: ` and the error to [`stderr`].
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use die::DieIo;
+    /// std::fs::read("/nonexistent").die_io("cannot read config"); // prints `cannot read config: No such file or directory (os error 2)` to stderr then exits with code 66
+    /// ```
+    ///
+    /// [`io::Result`]: https://doc.rust-lang.org/std/io/type.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`ErrorKind`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html
+    /// [`codes::from_io_error_kind`]: codes/fn.from_io_error_kind.html
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    fn die_io(self, msg: impl fmt::Display) -> T;
+}
+
+impl<T> DieIo<T> for io::Result<T> {
+    #[inline]
+    #[track_caller]
+    fn die_errno(self, msg: impl fmt::Display) -> T {
+        match self {
+            Ok(t) => t,
+            Err(err) => {
+                let code = err.raw_os_error().unwrap_or_else(default_exit_code);
+                print_exit_err(code, &msg, &err)
+            }
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_io(self, msg: impl fmt::Display) -> T {
+        match self {
+            Ok(t) => t,
+            Err(err) => {
+                let code = codes::from_io_error_kind(err.kind());
+                print_exit_err(code, &msg, &err)
+            }
+        }
+    }
+}
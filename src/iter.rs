@@ -0,0 +1,90 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::iter::FromIterator;
+
+use exit_code::DefaultExitCode;
+use {print_exit, IntoExitCode};
+
+/// `DieIter` is a trait implemented on iterators of [`Result`]s, to report every error at once
+/// instead of only the first
+///
+/// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+pub trait DieIter<T, E>: Iterator<Item = Result<T, E>> + Sized {
+    /// Collects the content of every [`Ok`], like collecting into a `Result` would.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(1)][exit] if there was any [`Err`], after running the iterator to
+    /// the end and printing every error, one per line, to [`stderr`].
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use die::DieIter;
+    /// let ports: Vec<u16> = ["80", "http", "443", "-1"]
+    ///     .iter()
+    ///     .map(|port| port.parse::<u16>())
+    ///     .collect_or_die(); // prints `invalid digit found in string` twice to stderr then exits with code 1
+    /// ```
+    ///
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    #[inline]
+    #[track_caller]
+    fn collect_or_die<B: FromIterator<T>>(self) -> B
+    where
+        E: fmt::Display,
+    {
+        self.collect_or_die_code(DefaultExitCode)
+    }
+
+    /// Collects the content of every [`Ok`], like collecting into a `Result` would.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if there was any [`Err`], after running the
+    /// iterator to the end and printing every error, one per line, to [`stderr`].
+    ///
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    #[track_caller]
+    fn collect_or_die_code<B: FromIterator<T>>(self, exit_code: impl IntoExitCode) -> B
+    where
+        E: fmt::Display,
+    {
+        let mut errors = Vec::new();
+        let collected = self
+            .filter_map(|result| result.map_err(|err| errors.push(err)).ok())
+            .collect();
+        if !errors.is_empty() {
+            print_exit(exit_code, Lines(&errors));
+        }
+        collected
+    }
+}
+
+impl<T, E, I: Iterator<Item = Result<T, E>>> DieIter<T, E> for I {}
+
+/// Every item on its own line
+pub(crate) struct Lines<'a, E: 'a>(pub(crate) &'a [E]);
+
+impl<E: fmt::Display> fmt::Display for Lines<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            item.fmt(f)?;
+        }
+        Ok(())
+    }
+}
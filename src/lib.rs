@@ -4,15 +4,231 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use std::process;
+#![cfg_attr(not(feature = "std"), no_std)]
 
-/// const of 1
+#[cfg(feature = "anyhow")]
+extern crate anyhow;
+#[cfg(feature = "clap")]
+extern crate clap;
+#[cfg(feature = "std")]
+extern crate core;
+#[cfg(feature = "derive")]
+extern crate die_derive;
+#[cfg(feature = "eyre")]
+extern crate eyre;
+#[cfg(all(any(feature = "syslog", feature = "signal"), unix))]
+extern crate libc;
+#[cfg(feature = "log")]
+extern crate log;
+#[cfg(feature = "miette")]
+extern crate miette;
+#[cfg(feature = "sentry")]
+extern crate sentry_core;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(all(feature = "wasm", target_arch = "wasm32", target_os = "unknown"))]
+extern crate wasm_bindgen;
+#[cfg(all(feature = "wasm", target_arch = "wasm32", target_os = "unknown"))]
+extern crate web_sys;
+
+use core::error::Error;
+use core::fmt;
+use core::ops::ControlFlow;
+use core::task::Poll;
+
+use exit_code::DefaultExitCode;
+
+#[doc(hidden)]
+pub mod __private;
+#[cfg(feature = "std")]
+mod abort;
+#[cfg(feature = "std")]
+mod boxed;
+#[cfg(feature = "std")]
+mod broken_pipe;
+mod chain;
+#[cfg(feature = "std")]
+mod cleanup;
+mod code;
+pub mod codes;
+#[cfg(feature = "std")]
+mod collector;
+#[cfg(feature = "std")]
+mod config;
+#[cfg(feature = "std")]
+mod crash_log;
+#[cfg(feature = "std")]
+mod crash_report;
+#[cfg(feature = "std")]
+mod diagnostic;
+mod die_code;
+#[cfg(feature = "std")]
+mod encode;
+mod exit_code;
+#[cfg(feature = "std")]
+mod fatal;
+#[cfg(feature = "std")]
+mod guard;
+#[cfg(feature = "std")]
+mod hook;
+#[cfg(feature = "i18n")]
+mod i18n;
+mod info;
+#[cfg(feature = "std")]
+mod integration;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "std")]
+mod iter;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "std")]
+mod lock;
+#[cfg(feature = "std")]
+mod log_file;
+#[cfg(feature = "logfmt")]
+mod logfmt;
+#[cfg(feature = "std")]
+mod message;
+#[cfg(not(feature = "std"))]
+mod nostd;
+#[cfg(feature = "std")]
+mod output;
+#[cfg(feature = "std")]
+mod panic_hook;
+#[cfg(feature = "std")]
+mod pause;
+#[cfg(feature = "std")]
+mod payload;
+pub mod prelude;
+#[cfg(feature = "tokio")]
+mod shutdown;
+#[cfg(all(feature = "signal", unix))]
+mod signal;
+mod snippet;
+#[cfg(feature = "std")]
+mod suggest;
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(feature = "std")]
+mod unwind;
+#[cfg(feature = "std")]
+mod usage;
+#[cfg(feature = "std")]
+mod warn;
+#[cfg(feature = "std")]
+mod watchdog;
+#[cfg(feature = "wrap")]
+mod wrap;
+
+#[cfg(feature = "std")]
+pub use boxed::DieBoxed;
+#[cfg(feature = "std")]
+pub use broken_pipe::set_broken_pipe;
+#[cfg(feature = "std")]
+pub use cleanup::{on_exit, remove_on_exit};
+pub use code::ExitCode;
+#[cfg(feature = "std")]
+pub use collector::ErrorCollector;
+#[cfg(feature = "eventlog")]
+pub use config::set_event_source;
+#[cfg(feature = "json")]
+pub use config::set_json;
+#[cfg(feature = "logcat")]
+pub use config::set_logcat_tag;
+#[cfg(feature = "logfmt")]
+pub use config::set_logfmt;
+#[cfg(feature = "std")]
+pub use config::{
+    config, set_backtrace, set_error_label, set_exit_delay, set_flush_stdout, set_location,
+    set_lock_stdout, set_no_exit, set_pause, set_perl_style, set_program_name, set_program_prefix,
+    set_quiet, set_thread_name, set_timestamp, set_verbosity, Color, Config,
+};
+#[cfg(feature = "std")]
+pub use crash_log::set_crash_log_dir;
+#[cfg(feature = "std")]
+pub use crash_report::set_crash_report;
+#[cfg(feature = "std")]
+pub use diagnostic::{fatal, Diagnostic};
+pub use die_code::{DieCode, ExitCodeFromError};
+#[cfg(feature = "derive")]
+pub use die_derive::{DieCode, IntoExitCode};
+pub use exit_code::{default_exit_code, IntoExitCode};
+#[cfg(feature = "std")]
+pub use fatal::{run, Fatal};
+#[cfg(feature = "std")]
+pub use guard::DieGuard;
+#[cfg(feature = "std")]
+pub use hook::{set_hook, take_hook};
+#[cfg(feature = "i18n")]
+pub use i18n::set_translator;
+pub use info::DieInfo;
+#[cfg(feature = "tracing")]
+pub use integration::drop_on_exit;
+#[cfg(feature = "statsd")]
+pub use integration::set_statsd;
+#[cfg(feature = "clap")]
+pub use integration::DieClap;
+#[cfg(feature = "serde_json")]
+pub use integration::DieJson;
+#[cfg(feature = "std")]
+pub use io::DieIo;
+#[cfg(feature = "std")]
+pub use iter::DieIter;
+#[cfg(feature = "std")]
+pub use lock::DieLock;
+#[cfg(feature = "std")]
+pub use log_file::set_log_file;
+#[cfg(not(feature = "std"))]
+pub use nostd::{set_exit, set_sink};
+#[cfg(feature = "std")]
+pub use output::{set_output, set_thread_output, take_output, take_thread_output};
+#[cfg(feature = "std")]
+pub use panic_hook::install_die_panic_hook;
+#[cfg(feature = "std")]
+pub use payload::die_with_payload;
+#[cfg(feature = "tokio")]
+pub use shutdown::{on_exit_async, set_shutdown_timeout};
+pub use snippet::{snippet, Snippet};
+#[cfg(feature = "std")]
+pub use suggest::suggest;
+#[cfg(feature = "panic")]
+pub use unwind::catch;
+#[cfg(feature = "std")]
+pub use unwind::Died;
+#[cfg(feature = "std")]
+pub use usage::{set_usage, USAGE_EXIT_CODE};
+#[cfg(feature = "std")]
+pub use warn::Warn;
+#[cfg(feature = "std")]
+pub use watchdog::{after, Watchdog};
+
+/// const of 1, the exit code used when none is given unless changed with
+/// [`Config::default_exit_code`]
+///
+/// [`Config::default_exit_code`]: struct.Config.html#method.default_exit_code
 pub const DEFAULT_EXIT_CODE: i32 = 1;
 
 /// Prints a message to [`stderr`] and terminates the current process with the specified exit code
 /// or 1 if no exit code is specified, by calling [`eprintln`]!() on all arguments followed by
 /// [process::exit(exit_code)][exit]
 ///
+/// A hook registered with [`set_hook`] runs in place of printing.
+///
+/// Formatted messages are passed along as [`fmt::Arguments`] and written straight to [`stderr`],
+/// no intermediate [`String`] is built.
+///
+/// [`set_hook`]: fn.set_hook.html
+/// [`ExitCode`]: https://doc.rust-lang.org/std/process/struct.ExitCode.html
+/// [`Error`]: https://doc.rust-lang.org/std/error/trait.Error.html
+/// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// [`source`]: https://doc.rust-lang.org/std/error/trait.Error.html#method.source
+/// [`fmt::Arguments`]: https://doc.rust-lang.org/std/fmt/struct.Arguments.html
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
 /// [`eprintln`]: https://doc.rust-lang.org/std/macro.eprintln.html
 /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
 /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
@@ -21,61 +237,400 @@ pub const DEFAULT_EXIT_CODE: i32 = 1;
 ///
 /// Basic usage:
 ///
-/// ```{.should_panic}
+/// ```should_panic
 /// # use die::die;
 /// die!("argument to -e must be numeric"); // prints message to stderr then exits with code 1
 /// ```
 /// With custom error code:
-/// ```{.should_panic}
+/// ```should_panic
 /// # use die::die;
 /// die!(2; "argument to -e must be numeric"); // prints message to stderr then exits with code 2
 /// ```
 /// error code can go at the beginning or end, just separate with colon:
-/// ```{.should_panic}
+/// ```should_panic
 /// # use die::die;
 /// die!("argument to -e must be numeric"; 3); // prints message to stderr then exits with code 3
 /// ```
 /// supports all the formatting eprintln! does:
-/// ```{.should_panic}
+/// ```should_panic
 /// # use die::die;
 /// die!("argument {} must be {}", "-e", 1; 4); // prints `argument -e must be 1` to stderr then exits with code 4
 /// ```
 /// supports all the formatting eprintln! does without exit code too:
-/// ```{.should_panic}
+/// ```should_panic
 /// # use die::die;
 /// die!("argument {} must be {}", "-e", 1); // prints `argument -e must be 1` to stderr then exits with code 1
 /// ```
+/// [`ExitCode`] works anywhere an exit code does:
+/// ```should_panic
+/// # use die::die;
+/// use std::process::ExitCode;
+/// die!(ExitCode::FAILURE; "argument to -e must be numeric"); // prints message to stderr then exits with code 1
+/// ```
+/// any [`Error`] can be passed directly, its [`Display`] output is the message, followed by an
+/// indented `caused by:` line for each error in its [`source`] chain:
+/// ```should_panic
+/// # use die::die;
+/// let err = "http".parse::<u16>().unwrap_err();
+/// die!(err; 5); // prints `invalid digit found in string` to stderr then exits with code 5
+/// ```
+/// ```should_panic
+/// # use die::die;
+/// let err = std::fs::read("/nonexistent").unwrap_err();
+/// die!(err); // prints `No such file or directory (os error 2)` to stderr then exits with code 1
+/// ```
+/// any [`Display`] works as the message next to an exit code:
+/// ```should_panic
+/// # use die::die;
+/// use std::borrow::Cow;
+/// die!(2; Cow::Borrowed("argument to -e must be numeric")); // prints message to stderr then exits with code 2
+/// ```
+/// or named, before or after the formatting arguments, without a `;`:
+/// ```should_panic
+/// # use die::die;
+/// die!(code = 2, "argument {} must be {}", "-e", 1); // prints `argument -e must be 1` to stderr then exits with code 2
+/// ```
+/// ```should_panic
+/// # use die::die;
+/// let flag = "-e";
+/// die!("argument {} must be numeric", flag, code = 2); // prints `argument -e must be numeric` to stderr then exits with code 2
+/// ```
+/// A trailing named format argument can't be called `code` then, put it first or rename it.
+///
 /// just exit with a code alone:
-/// ```{.should_panic}
+/// ```should_panic
 /// # use die::die;
 /// die!(2); // prints nothing, only exits with code 3
 /// ```
 /// just exit:
-/// ```{.should_panic}
+/// ```should_panic
 /// # use die::die;
 /// die!(); // prints nothing, only exits with code 1
 /// ```
+///
+/// # Allocation
+///
+/// A string literal message, or a plain [`Display`] one, with the default configuration and hook,
+/// is printed and exited on without a single heap allocation, as is `.die("message")`, so dying
+/// stays safe when memory runs out. Settings that build strings, like the program prefix, and the
+/// `panic` feature's unwinding, do allocate, and so do some integrations, like `sentry`, and std
+/// setting up the stdout buffer, if stdout wasn't used before it is flushed.
+///
+/// ```
+/// # use die::die;
+/// use std::alloc::{GlobalAlloc, Layout, System};
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// static ARMED: AtomicBool = AtomicBool::new(false);
+/// static ALLOCATED: AtomicBool = AtomicBool::new(false);
+///
+/// struct Watch;
+///
+/// unsafe impl GlobalAlloc for Watch {
+///     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+///         if ARMED.load(Ordering::Relaxed) {
+///             ALLOCATED.store(true, Ordering::Relaxed);
+///         }
+///         System.alloc(layout)
+///     }
+///     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+///         System.dealloc(ptr, layout)
+///     }
+/// }
+///
+/// #[global_allocator]
+/// static GLOBAL: Watch = Watch;
+///
+/// # // sentry sets up its hub the first time it is used, which allocates
+/// # if cfg!(feature = "sentry") {
+/// #     return;
+/// # }
+/// println!("working");
+/// // cleanups run after the message is printed, report whether anything allocated by then
+/// die::on_exit(|| std::process::exit(ALLOCATED.load(Ordering::Relaxed) as i32));
+/// ARMED.store(true, Ordering::Relaxed);
+/// die!("static message"; 2); // prints `static message` to stderr then exits with code 0 here
+/// ```
 #[macro_export]
 macro_rules! die {
-    () => (::std::process::exit(::die::DEFAULT_EXIT_CODE));
-    ($x:expr) => (::die::PrintExit::print_exit(&$x));
-    ($x:expr; $y:expr) => (::die::PrintExit::print_exit(&($x, $y)));
-    ($x:expr; $($y:expr),+) => ({
-        eprintln!($($y),+);
-        ::std::process::exit($x)
+    (payload = $payload:expr; $($arg:tt)*) => ({
+        $crate::__private::set_payload($crate::__private::Box::new($payload));
+        $crate::die!($($arg)*)
+    });
+    (code = $code:expr) => ($crate::PrintExit::print_exit(&$code));
+    (code = $code:expr, $($arg:tt)+) => ($crate::PrintExit::print_exit(&($code, $crate::__private::format_args!($($arg)+))));
+    // munches format arguments looking for a trailing `code = ...`
+    (@code [$($arg:tt)*] , code = $code:expr) => ($crate::PrintExit::print_exit(&($code, $crate::__private::format_args!($($arg)*))));
+    (@code [$($arg:tt)*] $next:tt $($rest:tt)*) => ($crate::die!(@code [$($arg)* $next] $($rest)*));
+    (@code [$($arg:tt)*]) => ($crate::PrintExit::print_exit(&$crate::__private::format_args!($($arg)*)));
+    () => ($crate::PrintExit::print_exit(&$crate::default_exit_code()));
+    ($x:expr) => ({
+        #[allow(unused_imports)]
+        use $crate::__private::{ErrorKind, PrintExitKind};
+        (&$crate::__private::Wrap(&$x)).die_kind()
+    });
+    ($x:expr; $y:expr) => ({
+        #[allow(unused_imports)]
+        use $crate::__private::{
+            CodeDisplayKind, CodeErrorKind, DisplayCodeKind, ErrorCodeKind, PrintExitKind,
+        };
+        (&&$crate::__private::Wrap(&($x, $y))).die_kind()
+    });
+    ($x:expr; $($y:expr),+) => ($crate::PrintExit::print_exit(&($x, $crate::__private::format_args!($($y),+))));
+    ($($y:expr),+; $x:expr) => ($crate::PrintExit::print_exit(&($crate::__private::format_args!($($y),+), $x)));
+    ($($arg:tt)*) => ($crate::die!(@code [] $($arg)*));
+}
+
+/// Dies like [`die`]!() if a condition is `true`, for guard clauses without an `if` block
+///
+/// Everything after the condition is passed on to [`die`]!(), so it takes the exit code and
+/// message forms [`die`]!() does.
+///
+/// [`die`]: macro.die.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die_if;
+/// let args = ["prog"];
+/// die_if!(args.len() < 2, 2; "usage: {} FILE", args[0]); // prints `usage: prog FILE` to stderr then exits with code 2
+/// ```
+#[macro_export]
+macro_rules! die_if {
+    ($cond:expr) => (if $cond { $crate::die!() });
+    ($cond:expr, $($arg:tt)*) => (if $cond { $crate::die!($($arg)*) });
+}
+
+/// Dies like [`die`]!() unless a condition is `true`, the inverse of [`die_if`]!()
+///
+/// [`die`]: macro.die.html
+/// [`die_if`]: macro.die_if.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die_unless;
+/// let config = std::path::Path::new("/nonexistent/config.toml");
+/// die_unless!(config.exists(), "{} is missing", config.display()); // prints `/nonexistent/config.toml is missing` to stderr then exits with code 1
+/// ```
+#[macro_export]
+macro_rules! die_unless {
+    ($cond:expr) => (if !$cond { $crate::die!() });
+    ($cond:expr, $($arg:tt)*) => (if !$cond { $crate::die!($($arg)*) });
+}
+
+/// Dies unless a condition is `true`, like anyhow's `ensure!` but exiting instead of returning
+/// an `Err`
+///
+/// Without a message it dies with `condition failed: ` followed by the condition's source code,
+/// otherwise everything after the condition is passed on to [`die`]!(), exit code included.
+///
+/// [`die`]: macro.die.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::ensure_or_die;
+/// let retries = 0;
+/// ensure_or_die!(retries > 0); // prints ``condition failed: `retries > 0` `` to stderr then exits with code 1
+/// ```
+/// ```should_panic
+/// # use die::ensure_or_die;
+/// let (start, end) = (5, 2);
+/// ensure_or_die!(start <= end, 3; "range {}..{} is backwards", start, end); // prints `range 5..2 is backwards` to stderr then exits with code 3
+/// ```
+#[macro_export]
+macro_rules! ensure_or_die {
+    ($cond:expr $(,)?) => (if !$cond {
+        $crate::die!($crate::__private::concat!("condition failed: `", $crate::__private::stringify!($cond), "`"))
+    });
+    ($cond:expr, $($arg:tt)+) => (if !$cond { $crate::die!($($arg)+) });
+}
+
+/// Evaluates to the content of an [`Ok`] or [`Some`], or dies like [`die`]!(), for fallible
+/// expressions in places a method chain on [`Die`] reads badly, like struct literals
+///
+/// Everything after the expression is passed on to [`die`]!(), exit code included. Without a
+/// message it dies with the expression's source code followed by ` failed`.
+///
+/// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+/// [`Some`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.Some
+/// [`die`]: macro.die.html
+/// [`Die`]: trait.Die.html
+///
+/// # Examples
+///
+/// ```
+/// # use die::or_die;
+/// struct Args {
+///     port: u16,
+///     host: String,
+/// }
+/// let args = Args {
+///     port: or_die!("8080".parse(), "invalid port"; 2),
+///     host: or_die!(std::env::args().next(), "no program name"),
+/// };
+/// assert_eq!(args.port, 8080);
+/// ```
+/// ```should_panic
+/// # use die::or_die;
+/// let port: u16 = or_die!("http".parse(), 2; "invalid port {}", "http"); // prints `invalid port http` to stderr then exits with code 2
+/// ```
+#[macro_export]
+macro_rules! or_die {
+    ($x:expr $(,)?) => (match $crate::__private::OrDie::or_die($x) {
+        $crate::__private::Option::Some(value) => value,
+        $crate::__private::Option::None => $crate::die!($crate::__private::concat!($crate::__private::stringify!($x), " failed")),
     });
-    ($($y:expr),+; $x:expr) => ({
-        eprintln!($($y),+);
-        ::std::process::exit($x)
+    ($x:expr, $($arg:tt)+) => (match $crate::__private::OrDie::or_die($x) {
+        $crate::__private::Option::Some(value) => value,
+        $crate::__private::Option::None => $crate::die!($($arg)+),
+    });
+}
+
+/// Like [`die`]!(), but prints the message to [`stdout`] instead of [`stderr`], for tools whose
+/// protocol expects the final status there while still exiting with a nonzero code
+///
+/// Takes everything [`die`]!() does.
+///
+/// [`die`]: macro.die.html
+/// [`stdout`]: https://doc.rust-lang.org/std/io/fn.stdout.html
+/// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die_out;
+/// die_out!("status: {}", "failed"; 3); // prints `status: failed` to stdout then exits with code 3
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! die_out {
+    ($($arg:tt)*) => ({
+        $crate::__private::to_stdout();
+        $crate::die!($($arg)*)
     });
+}
+
+/// Like [`die`]!(), but aborts the process with [`process::abort`] instead of exiting, so a core
+/// dump or crash handler fires, for invariant violations better debugged than cleanly exited
+///
+/// Takes everything [`die`]!() does, the exit code is only seen by hooks, or unwound with under
+/// the `panic` feature. The hook and cleanups still run before aborting.
+///
+/// [`die`]: macro.die.html
+/// [`process::abort`]: https://doc.rust-lang.org/std/process/fn.abort.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die_abort;
+/// let (len, cap) = (5, 4);
+/// die_abort!("len {} > cap {}", len, cap); // prints `len 5 > cap 4` then aborts
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! die_abort {
     ($($arg:tt)*) => ({
-        eprintln!($($arg)*);
-        ::std::process::exit(::die::DEFAULT_EXIT_CODE)
+        $crate::__private::to_abort();
+        $crate::die!($($arg)*)
+    });
+}
+
+/// Dies with an error message followed by the usage text registered with [`set_usage`], for
+/// bad command line arguments, exiting with [`USAGE_EXIT_CODE`]
+///
+/// Takes a single value to display or the formatting [`eprintln`]!() does, or nothing to print
+/// only the usage.
+///
+/// [`set_usage`]: fn.set_usage.html
+/// [`USAGE_EXIT_CODE`]: constant.USAGE_EXIT_CODE.html
+/// [`eprintln`]: https://doc.rust-lang.org/std/macro.eprintln.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die_usage;
+/// die::set_usage("usage: myprog [-v] FILE...");
+/// let flag = "-q";
+/// die_usage!("unknown flag {}", flag); // prints `unknown flag -q`, a blank line, then the usage to stderr then exits with code 2
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! die_usage {
+    () => ($crate::__private::die_usage($crate::__private::Option::None));
+    ($x:expr) => ($crate::__private::die_usage($crate::__private::Option::Some(&$x)));
+    ($($arg:tt)+) => ($crate::__private::die_usage($crate::__private::Option::Some(&$crate::__private::format_args!($($arg)+))));
+}
+
+/// Prints a warning like [`die`]!() prints its message, with the same program prefix, location,
+/// color and output, but keeps running
+///
+/// The label is `warning: `, bold yellow with the `color` feature, when [`set_error_label`] is
+/// enabled. Takes a single value to display or the formatting [`eprintln`]!() does, see [`Warn`]
+/// for [`Option`] and [`Result`].
+///
+/// [`die`]: macro.die.html
+/// [`set_error_label`]: fn.set_error_label.html
+/// [`eprintln`]: https://doc.rust-lang.org/std/macro.eprintln.html
+/// [`Warn`]: trait.Warn.html
+/// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+/// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+///
+/// # Examples
+///
+/// ```
+/// # use die::warn;
+/// warn!("retrying in {} seconds", 5); // prints `retrying in 5 seconds` to stderr and keeps going
+/// let err = "http".parse::<u16>().unwrap_err();
+/// warn!(err); // prints `invalid digit found in string` to stderr and keeps going
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! warn {
+    ($x:expr) => ($crate::__private::warn(&$x));
+    ($($arg:tt)+) => ($crate::__private::warn(&$crate::__private::format_args!($($arg)+)));
+}
+
+/// Like [`die`]!(), but kills the process by raising a real signal instead of exiting, so the
+/// parent sees a signal death and shells report 128+n, unix only
+///
+/// The signal, like `libc::SIGTERM`, comes first, then optionally `;` and anything [`die`]!()
+/// takes as a message. It is raised after the hook and cleanups ran, with its default action and
+/// unblocked; if that doesn't kill the process, like for `SIGCHLD`, it exits with 128+n, which is
+/// also the code hooks see. With the `panic` feature it unwinds with that code instead.
+///
+/// [`die`]: macro.die.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die_signal;
+/// die_signal!(15; "terminating"); // prints `terminating` then dies of SIGTERM, so $? is 143
+/// ```
+#[cfg(all(feature = "signal", unix))]
+#[macro_export]
+macro_rules! die_signal {
+    ($signal:expr) => ({
+        let signal: i32 = $signal;
+        $crate::__private::to_signal(signal);
+        $crate::die!(128 + signal)
+    });
+    ($signal:expr; $($arg:tt)+) => ({
+        let signal: i32 = $signal;
+        $crate::__private::to_signal(signal);
+        $crate::die!(128 + signal; $($arg)+)
     });
 }
 
 /// `Die` is a trait implemented on [`Result`] and [`Option`] to make exiting with messages and codes easy
 ///
+/// It is implemented on `bool` too, dying when it is `false`, for guard conditions like
+/// `path.exists().die("config missing")`, and on [`ControlFlow`], dying on a `Break`, for
+/// visitors and iteration that stop early with it. A `Poll<Result<T, E>>` becomes a `Poll<T>`,
+/// staying `Pending` and dying on a ready `Err`, for hand written futures and poll loops.
+///
+/// [`ControlFlow`]: https://doc.rust-lang.org/std/ops/enum.ControlFlow.html
 /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
 /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
 pub trait Die<T> {
@@ -99,17 +654,46 @@ pub trait Die<T> {
     ///
     /// Basic usage:
     ///
-    /// ```{.should_panic}
+    /// ```should_panic
     /// # use die::Die;
     /// let x: Result<u32, &str> = Err("emergency failure");
     /// x.die("strange error"); // prints `strange error` to stderr then exits with code 1
     /// ```
-    /// ```{.should_panic}
+    /// ```should_panic
     /// # use die::Die;
     /// let x: Option<u32> = None;
     /// x.die("strange error"); // prints `strange error` to stderr then exits with code 1
     /// ```
-    fn die(self, msg: &str) -> T;
+    /// ```should_panic
+    /// # use die::Die;
+    /// std::path::Path::new("/nonexistent").exists().die("config missing"); // prints `config missing` to stderr then exits with code 1
+    /// ```
+    /// ```should_panic
+    /// # use die::Die;
+    /// use std::ops::ControlFlow;
+    /// let visited = [1, -2, 3].iter().try_for_each(|&n| match n < 0 {
+    ///     true => ControlFlow::Break(n),
+    ///     false => ControlFlow::Continue(()),
+    /// });
+    /// visited.die("negative number"); // prints `negative number` to stderr then exits with code 1
+    /// ```
+    /// ```should_panic
+    /// # use die::Die;
+    /// use std::task::Poll;
+    /// let poll: Poll<Result<usize, &str>> = Poll::Ready(Err("connection reset"));
+    /// let read: Poll<usize> = poll.die("socket error"); // prints `socket error` to stderr then exits with code 1
+    /// ```
+    /// Anything implementing [`Display`] works as the message, without allocating a [`String`]:
+    /// ```should_panic
+    /// # use die::Die;
+    /// let port = 70000;
+    /// let x: Option<u16> = None;
+    /// x.die(format_args!("invalid port {}", port)); // prints `invalid port 70000` to stderr then exits with code 1
+    /// ```
+    ///
+    /// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+    /// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+    fn die(self, msg: impl fmt::Display) -> T;
 
     /// Unwraps a [`Result`] or [`Option`], yielding the content of an [`Ok`] or [`Some`].
     ///
@@ -131,102 +715,1179 @@ pub trait Die<T> {
     ///
     /// Basic usage:
     ///
-    /// ```{.should_panic}
+    /// ```should_panic
     /// # use die::Die;
     /// let x: Result<u32, &str> = Err("emergency failure");
     /// x.die_code("strange", 3); // prints `strange` to stderr then exits with code 3
     /// ```
-    /// ```{.should_panic}
+    /// ```should_panic
     /// # use die::Die;
     /// let x: Option<u32> = None;
     /// x.die_code("strange", 3); // prints `strange` to stderr then exits with code 3
     /// ```
-    fn die_code(self, msg: &str, exit_code: i32) -> T;
+    fn die_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T;
+
+    /// Unwraps a [`Result`] or [`Option`], yielding the content of an [`Ok`] or [`Some`].
+    ///
+    /// The message is only built by calling `f` if the value is an [`Err`] or [`None`], so nothing
+    /// is formatted or allocated on the success path.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(1)][exit] if the value is an [`Err`] or [`None`], after printing the
+    /// message returned by `f` to [`stderr`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [`Some`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.Some
+    /// [`None`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::Die;
+    /// let path = "/etc/missing.conf";
+    /// let x: Result<u32, &str> = Err("emergency failure");
+    /// x.die_with(|| format!("cannot read {}", path)); // prints `cannot read /etc/missing.conf` to stderr then exits with code 1
+    /// ```
+    /// ```should_panic
+    /// # use die::Die;
+    /// let x: Option<u32> = None;
+    /// x.die_with(|| format!("strange {}", "error")); // prints `strange error` to stderr then exits with code 1
+    /// ```
+    #[cfg(feature = "std")]
+    fn die_with(self, f: impl FnOnce() -> String) -> T;
+
+    /// Unwraps a [`Result`] or [`Option`], yielding the content of an [`Ok`] or [`Some`].
+    ///
+    /// The message is only built by calling `f` if the value is an [`Err`] or [`None`], so nothing
+    /// is formatted or allocated on the success path.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the value is an [`Err`] or [`None`], after printing the
+    /// message returned by `f` to [`stderr`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [`Some`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.Some
+    /// [`None`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::Die;
+    /// let x: Result<u32, &str> = Err("emergency failure");
+    /// x.die_code_with(|| format!("strange {}", "error"), 3); // prints `strange error` to stderr then exits with code 3
+    /// ```
+    /// ```should_panic
+    /// # use die::Die;
+    /// let x: Option<u32> = None;
+    /// x.die_code_with(|| format!("strange {}", "error"), 3); // prints `strange error` to stderr then exits with code 3
+    /// ```
+    #[cfg(feature = "std")]
+    fn die_code_with(self, f: impl FnOnce() -> String, exit_code: impl IntoExitCode) -> T;
+
+    /// Unwraps a [`Result`] or [`Option`], yielding the content of an [`Ok`] or [`Some`].
+    ///
+    /// Takes pre-built [`fmt::Arguments`] from [`format_args`]!() so the message is written
+    /// straight to [`stderr`] without allocating.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the value is an [`Err`] or [`None`], after printing the
+    /// formatted message to [`stderr`].
+    ///
+    /// [`fmt::Arguments`]: https://doc.rust-lang.org/std/fmt/struct.Arguments.html
+    /// [`format_args`]: https://doc.rust-lang.org/std/macro.format_args.html
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [`Some`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.Some
+    /// [`None`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::Die;
+    /// let x: Result<u32, &str> = Err("emergency failure");
+    /// x.die_fmt(format_args!("strange {}", 42), 3); // prints `strange 42` to stderr then exits with code 3
+    /// ```
+    /// ```should_panic
+    /// # use die::Die;
+    /// let x: Option<u32> = None;
+    /// x.die_fmt(format_args!("strange {}", 42), 3); // prints `strange 42` to stderr then exits with code 3
+    /// ```
+    fn die_fmt(self, args: fmt::Arguments, exit_code: impl IntoExitCode) -> T;
+
+    /// Like [`die_code`], but prints the message to [`stdout`] instead of [`stderr`], for
+    /// protocols that expect the final status there, see [`die_out`]!()
+    ///
+    /// [`die_code`]: #tymethod.die_code
+    /// [`die_out`]: macro.die_out.html
+    /// [`stdout`]: https://doc.rust-lang.org/std/io/fn.stdout.html
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use die::Die;
+    /// let x: Option<u32> = None;
+    /// x.die_out("status: failed", 3); // prints `status: failed` to stdout then exits with code 3
+    /// ```
+    #[cfg(feature = "std")]
+    fn die_out(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T;
+
+    /// Like [`die`], but aborts the process instead of exiting, see [`die_abort`]!()
+    ///
+    /// [`die`]: #tymethod.die
+    /// [`die_abort`]: macro.die_abort.html
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use die::Die;
+    /// let x: Option<u32> = None;
+    /// x.die_abort("invariant violated"); // prints `invariant violated` then aborts
+    /// ```
+    #[cfg(feature = "std")]
+    fn die_abort(self, msg: impl fmt::Display) -> T;
+
+    /// Unwraps like [`die_code`], but exits without printing anything, like `die!(code)`, for
+    /// protocols where the exit code is the whole contract and stderr output would pollute a
+    /// pipeline
+    ///
+    /// [`die_code`]: #tymethod.die_code
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use die::Die;
+    /// let x: Option<u32> = None;
+    /// x.die_quiet(3); // prints nothing, only exits with code 3
+    /// ```
+    fn die_quiet(self, exit_code: impl IntoExitCode) -> T;
 }
 
 impl<T, E> Die<T> for Result<T, E> {
     #[inline]
-    fn die(self, msg: &str) -> T {
-        self.die_code(msg, DEFAULT_EXIT_CODE)
+    #[track_caller]
+    fn die(self, msg: impl fmt::Display) -> T {
+        self.die_code(msg, DefaultExitCode)
     }
     #[inline]
-    fn die_code(self, msg: &str, exit_code: i32) -> T {
+    #[track_caller]
+    fn die_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T {
         match self {
             Ok(t) => t,
-            Err(_) => PrintExit::print_exit(&(exit_code, msg)),
+            Err(_) => print_exit(exit_code, msg),
         }
     }
-}
-
-impl<T> Die<T> for Option<T> {
+    #[cfg(feature = "std")]
     #[inline]
-    fn die(self, msg: &str) -> T {
-        self.die_code(msg, DEFAULT_EXIT_CODE)
+    #[track_caller]
+    fn die_with(self, f: impl FnOnce() -> String) -> T {
+        self.die_code_with(f, DefaultExitCode)
     }
+    #[cfg(feature = "std")]
     #[inline]
-    fn die_code(self, msg: &str, exit_code: i32) -> T {
+    #[track_caller]
+    fn die_code_with(self, f: impl FnOnce() -> String, exit_code: impl IntoExitCode) -> T {
         match self {
-            Some(t) => t,
-            None => PrintExit::print_exit(&(exit_code, msg)),
+            Ok(t) => t,
+            Err(_) => print_exit(exit_code, f()),
         }
     }
-}
-
-pub trait PrintExit {
-    fn print_exit(&self) -> !;
-}
-
-impl PrintExit for i32 {
     #[inline]
+    #[track_caller]
+    fn die_fmt(self, args: fmt::Arguments, exit_code: impl IntoExitCode) -> T {
+        match self {
+            Ok(t) => t,
+            Err(_) => print_exit(exit_code, args),
+        }
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_out(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T {
+        match self {
+            Ok(t) => t,
+            Err(_) => {
+                output::to_stdout();
+                print_exit(exit_code, msg)
+            }
+        }
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_abort(self, msg: impl fmt::Display) -> T {
+        match self {
+            Ok(t) => t,
+            Err(_) => {
+                abort::to_abort();
+                print_exit(DefaultExitCode, msg)
+            }
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_quiet(self, exit_code: impl IntoExitCode) -> T {
+        match self {
+            Ok(t) => t,
+            Err(_) => exit_quiet(exit_code),
+        }
+    }
+}
+
+impl<T> Die<T> for Option<T> {
+    #[inline]
+    #[track_caller]
+    fn die(self, msg: impl fmt::Display) -> T {
+        self.die_code(msg, DefaultExitCode)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T {
+        match self {
+            Some(t) => t,
+            None => print_exit(exit_code, msg),
+        }
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_with(self, f: impl FnOnce() -> String) -> T {
+        self.die_code_with(f, DefaultExitCode)
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_code_with(self, f: impl FnOnce() -> String, exit_code: impl IntoExitCode) -> T {
+        match self {
+            Some(t) => t,
+            None => print_exit(exit_code, f()),
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_fmt(self, args: fmt::Arguments, exit_code: impl IntoExitCode) -> T {
+        match self {
+            Some(t) => t,
+            None => print_exit(exit_code, args),
+        }
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_out(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T {
+        match self {
+            Some(t) => t,
+            None => {
+                output::to_stdout();
+                print_exit(exit_code, msg)
+            }
+        }
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_abort(self, msg: impl fmt::Display) -> T {
+        match self {
+            Some(t) => t,
+            None => {
+                abort::to_abort();
+                print_exit(DefaultExitCode, msg)
+            }
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_quiet(self, exit_code: impl IntoExitCode) -> T {
+        match self {
+            Some(t) => t,
+            None => exit_quiet(exit_code),
+        }
+    }
+}
+
+impl Die<()> for bool {
+    #[inline]
+    #[track_caller]
+    fn die(self, msg: impl fmt::Display) {
+        self.then_some(()).die(msg)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) {
+        self.then_some(()).die_code(msg, exit_code)
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_with(self, f: impl FnOnce() -> String) {
+        self.then_some(()).die_with(f)
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_code_with(self, f: impl FnOnce() -> String, exit_code: impl IntoExitCode) {
+        self.then_some(()).die_code_with(f, exit_code)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_fmt(self, args: fmt::Arguments, exit_code: impl IntoExitCode) {
+        self.then_some(()).die_fmt(args, exit_code)
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_out(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) {
+        self.then_some(()).die_out(msg, exit_code)
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_abort(self, msg: impl fmt::Display) {
+        self.then_some(()).die_abort(msg)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_quiet(self, exit_code: impl IntoExitCode) {
+        self.then_some(()).die_quiet(exit_code)
+    }
+}
+
+impl<B, C> Die<C> for ControlFlow<B, C> {
+    #[inline]
+    #[track_caller]
+    fn die(self, msg: impl fmt::Display) -> C {
+        self.continue_value().die(msg)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> C {
+        self.continue_value().die_code(msg, exit_code)
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_with(self, f: impl FnOnce() -> String) -> C {
+        self.continue_value().die_with(f)
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_code_with(self, f: impl FnOnce() -> String, exit_code: impl IntoExitCode) -> C {
+        self.continue_value().die_code_with(f, exit_code)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_fmt(self, args: fmt::Arguments, exit_code: impl IntoExitCode) -> C {
+        self.continue_value().die_fmt(args, exit_code)
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_out(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> C {
+        self.continue_value().die_out(msg, exit_code)
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_abort(self, msg: impl fmt::Display) -> C {
+        self.continue_value().die_abort(msg)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_quiet(self, exit_code: impl IntoExitCode) -> C {
+        self.continue_value().die_quiet(exit_code)
+    }
+}
+
+impl<T, E> Die<Poll<T>> for Poll<Result<T, E>> {
+    #[inline]
+    #[track_caller]
+    fn die(self, msg: impl fmt::Display) -> Poll<T> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.die(msg)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> Poll<T> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.die_code(msg, exit_code)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_with(self, f: impl FnOnce() -> String) -> Poll<T> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.die_with(f)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_code_with(self, f: impl FnOnce() -> String, exit_code: impl IntoExitCode) -> Poll<T> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.die_code_with(f, exit_code)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_fmt(self, args: fmt::Arguments, exit_code: impl IntoExitCode) -> Poll<T> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.die_fmt(args, exit_code)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_out(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> Poll<T> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.die_out(msg, exit_code)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_abort(self, msg: impl fmt::Display) -> Poll<T> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.die_abort(msg)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_quiet(self, exit_code: impl IntoExitCode) -> Poll<T> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.die_quiet(exit_code)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// `DieResult` is a trait implemented on [`Result`] for exiting with messages that include the
+/// error itself, instead of silently discarding it like [`Die`] does
+///
+/// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+/// [`Die`]: trait.Die.html
+pub trait DieResult<T, E> {
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(1)][exit] if the value is an [`Err`], after printing the passed
+    /// message followed by `: ` and the error's alternate [`Display`] output (`{:#}`) to
+    /// [`stderr`], which includes the whole context chain for errors like [`anyhow::Error`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+    /// [`anyhow::Error`]: https://docs.rs/anyhow/1/anyhow/struct.Error.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::DieResult;
+    /// let x: Result<u32, &str> = Err("emergency failure");
+    /// x.die_err("strange error"); // prints `strange error: emergency failure` to stderr then exits with code 1
+    /// ```
+    fn die_err(self, msg: impl fmt::Display) -> T
+    where
+        E: fmt::Display;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the value is an [`Err`], after printing the passed
+    /// message followed by `: ` and the error's alternate [`Display`] output (`{:#}`) to
+    /// [`stderr`], which includes the whole context chain for errors like [`anyhow::Error`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+    /// [`anyhow::Error`]: https://docs.rs/anyhow/1/anyhow/struct.Error.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::DieResult;
+    /// let x: Result<u32, &str> = Err("emergency failure");
+    /// x.die_err_code("strange", 3); // prints `strange: emergency failure` to stderr then exits with code 3
+    /// ```
+    fn die_err_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T
+    where
+        E: fmt::Display;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// Useful for error types that only implement [`Debug`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(1)][exit] if the value is an [`Err`], after printing the passed
+    /// message followed by `: ` and the error's [`Debug`] output to [`stderr`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::DieResult;
+    /// #[derive(Debug)]
+    /// struct Opaque(u8);
+    /// let x: Result<u32, Opaque> = Err(Opaque(7));
+    /// x.die_debug("strange error"); // prints `strange error: Opaque(7)` to stderr then exits with code 1
+    /// ```
+    fn die_debug(self, msg: impl fmt::Display) -> T
+    where
+        E: fmt::Debug;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// Useful for error types that only implement [`Debug`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the value is an [`Err`], after printing the passed
+    /// message followed by `: ` and the error's [`Debug`] output to [`stderr`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::DieResult;
+    /// #[derive(Debug)]
+    /// struct Opaque(u8);
+    /// let x: Result<u32, Opaque> = Err(Opaque(7));
+    /// x.die_debug_code("strange", 3); // prints `strange: Opaque(7)` to stderr then exits with code 3
+    /// ```
+    fn die_debug_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T
+    where
+        E: fmt::Debug;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// Useful when the error text is already meant for users, so prefixing it would be redundant.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(1)][exit] if the value is an [`Err`], after printing only the error's
+    /// alternate [`Display`] output (`{:#}`) to [`stderr`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::DieResult;
+    /// let x: Result<u32, &str> = Err("no such user: bob");
+    /// x.die_display(); // prints `no such user: bob` to stderr then exits with code 1
+    /// ```
+    fn die_display(self) -> T
+    where
+        E: fmt::Display;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// Useful when the error text is already meant for users, so prefixing it would be redundant.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the value is an [`Err`], after printing only the
+    /// error's alternate [`Display`] output (`{:#}`) to [`stderr`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::DieResult;
+    /// let x: Result<u32, &str> = Err("no such user: bob");
+    /// x.die_display_code(67); // prints `no such user: bob` to stderr then exits with code 67
+    /// ```
+    fn die_display_code(self, exit_code: impl IntoExitCode) -> T
+    where
+        E: fmt::Display;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// Like anyhow's `.context()`, without pulling in anyhow, but exiting instead of wrapping.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(1)][exit] if the value is an [`Err`], after printing `ctx`
+    /// followed by `: `, the error, and each of its [`source`]s on an indented `caused by:` line
+    /// to [`stderr`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`source`]: https://doc.rust-lang.org/std/error/trait.Error.html#method.source
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::DieResult;
+    /// let x: Result<u8, _> = "x".parse::<u8>();
+    /// x.die_context("invalid port"); // prints `invalid port: invalid digit found in string` to stderr then exits with code 1
+    /// ```
+    fn die_context(self, ctx: impl fmt::Display) -> T
+    where
+        E: Error;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// Like anyhow's `.context()`, without pulling in anyhow, but exiting instead of wrapping.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the value is an [`Err`], after printing `ctx`
+    /// followed by `: `, the error, and each of its [`source`]s on an indented `caused by:` line
+    /// to [`stderr`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`source`]: https://doc.rust-lang.org/std/error/trait.Error.html#method.source
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::DieResult;
+    /// let x: Result<u8, _> = "x".parse::<u8>();
+    /// x.die_context_code("invalid port", 64); // prints `invalid port: invalid digit found in string` to stderr then exits with code 64
+    /// ```
+    fn die_context_code(self, ctx: impl fmt::Display, exit_code: impl IntoExitCode) -> T
+    where
+        E: Error;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// The message is built from the error by calling `f` only if the value is an [`Err`], so it
+    /// can be worded differently depending on what went wrong.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(1)][exit] if the value is an [`Err`], after printing the message
+    /// returned by `f` to [`stderr`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::DieResult;
+    /// use std::io::ErrorKind;
+    /// std::fs::read("/nonexistent").die_map(|err| match err.kind() {
+    ///     ErrorKind::NotFound => "no config, run `myprog init` first".to_string(),
+    ///     _ => format!("cannot read config: {}", err),
+    /// }); // prints `no config, run `myprog init` first` to stderr then exits with code 1
+    /// ```
+    #[cfg(feature = "std")]
+    fn die_map(self, f: impl FnOnce(&E) -> String) -> T;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// The message is built from the error by calling `f` only if the value is an [`Err`], so it
+    /// can be worded differently depending on what went wrong.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the value is an [`Err`], after printing the
+    /// message returned by `f` to [`stderr`].
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```should_panic
+    /// # use die::DieResult;
+    /// let x: Result<u32, &str> = Err("emergency failure");
+    /// x.die_map_code(|err| format!("strange: {}", err), 3); // prints `strange: emergency failure` to stderr then exits with code 3
+    /// ```
+    #[cfg(feature = "std")]
+    fn die_map_code(self, f: impl FnOnce(&E) -> String, exit_code: impl IntoExitCode) -> T;
+
+    /// Unwraps a [`Result`], yielding the content of an [`Ok`].
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the value is an [`Err`], after printing the
+    /// error's own message to [`stderr`], both the message and exit code come from its
+    /// [`DieCode`] implementation.
+    ///
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`DieCode`]: trait.DieCode.html
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Ok`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Ok
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use die::{DieCode, DieResult};
+    /// # use std::fmt;
+    /// struct Timeout;
+    /// impl DieCode for Timeout {
+    ///     fn exit_code(&self) -> i32 {
+    ///         124
+    ///     }
+    ///     fn die_message(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    ///         f.write_str("timed out")
+    ///     }
+    /// }
+    /// let x: Result<u32, Timeout> = Err(Timeout);
+    /// x.die_auto(); // prints `timed out` to stderr then exits with code 124
+    /// ```
+    fn die_auto(self) -> T
+    where
+        E: DieCode;
+}
+
+impl<T, E> DieResult<T, E> for Result<T, E> {
+    #[inline]
+    #[track_caller]
+    fn die_err(self, msg: impl fmt::Display) -> T
+    where
+        E: fmt::Display,
+    {
+        self.die_err_code(msg, DefaultExitCode)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_err_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T
+    where
+        E: fmt::Display,
+    {
+        match self {
+            Ok(t) => t,
+            Err(e) => print_exit_err(exit_code.into_exit_code(), &msg, &e),
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_debug(self, msg: impl fmt::Display) -> T
+    where
+        E: fmt::Debug,
+    {
+        self.die_debug_code(msg, DefaultExitCode)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_debug_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> T
+    where
+        E: fmt::Debug,
+    {
+        match self {
+            Ok(t) => t,
+            Err(e) => print_exit(exit_code, format_args!("{}: {:?}", msg, e)),
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_display(self) -> T
+    where
+        E: fmt::Display,
+    {
+        self.die_display_code(DefaultExitCode)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_display_code(self, exit_code: impl IntoExitCode) -> T
+    where
+        E: fmt::Display,
+    {
+        match self {
+            Ok(t) => t,
+            Err(e) => print_exit(exit_code, format_args!("{:#}", e)),
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_context(self, ctx: impl fmt::Display) -> T
+    where
+        E: Error,
+    {
+        self.die_context_code(ctx, DefaultExitCode)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_context_code(self, ctx: impl fmt::Display, exit_code: impl IntoExitCode) -> T
+    where
+        E: Error,
+    {
+        match self {
+            Ok(t) => t,
+            Err(e) => print_exit_context(exit_code.into_exit_code(), &ctx, &e),
+        }
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_map(self, f: impl FnOnce(&E) -> String) -> T {
+        self.die_map_code(f, DefaultExitCode)
+    }
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn die_map_code(self, f: impl FnOnce(&E) -> String, exit_code: impl IntoExitCode) -> T {
+        match self {
+            Ok(t) => t,
+            Err(e) => print_exit(exit_code, f(&e)),
+        }
+    }
+    #[inline]
+    #[track_caller]
+    fn die_auto(self) -> T
+    where
+        E: DieCode,
+    {
+        match self {
+            Ok(t) => t,
+            Err(e) => print_exit(e.exit_code(), die_code::DieMessage(&e)),
+        }
+    }
+}
+
+/// Prints `msg` to [`stderr`] (or whatever the registered hook does) then exits with `exit_code`
+///
+/// Only converts its arguments before handing them to the outlined [`print_exit_dyn`], so every
+/// call site stays a couple of instructions.
+///
+/// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+#[inline]
+#[track_caller]
+fn print_exit(exit_code: impl IntoExitCode, msg: impl fmt::Display) -> ! {
+    print_exit_dyn(exit_code.into_exit_code(), &msg)
+}
+
+/// The one copy of the print and exit machinery behind [`print_exit`], kept out of line and out
+/// of the hot path of its callers
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn print_exit_dyn(exit_code: i32, msg: &dyn fmt::Display) -> ! {
+    exit(DieInfo::new(Some(msg), exit_code))
+}
+
+/// Prints `msg: err`, with the alternate [`Display`] output (`{:#}`) of `err`, then exits, out of
+/// line like [`print_exit_dyn`] so the formatting isn't repeated at every call site
+///
+/// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn print_exit_err(exit_code: i32, msg: &dyn fmt::Display, err: &dyn fmt::Display) -> ! {
+    print_exit_dyn(exit_code, &format_args!("{}: {:#}", msg, err))
+}
+
+/// Prints `err` with its whole source chain then exits, or quietly exits on a broken pipe if
+/// enabled, the one copy behind every [`die`]!(err)
+///
+/// Generic over the exit code only so a `DefaultExitCode` is looked up in here rather than at
+/// every call site, there is one copy per exit code type.
+///
+/// [`die`]: macro.die.html
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn print_exit_chain(exit_code: impl IntoExitCode, err: &dyn Error) -> ! {
+    #[cfg(feature = "std")]
+    broken_pipe::check_sources(err);
+    print_exit_dyn(exit_code.into_exit_code(), &chain::Chain(err))
+}
+
+/// Like [`print_exit_chain`], after `ctx: `, for `die_context` and the like
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn print_exit_context(exit_code: i32, ctx: &dyn fmt::Display, err: &dyn Error) -> ! {
+    #[cfg(feature = "std")]
+    broken_pipe::check_sources(err);
+    print_exit_dyn(exit_code, &format_args!("{}: {}", ctx, chain::Chain(err)))
+}
+
+/// Exits with `exit_code` without a message, like `die!(code)`
+#[inline]
+#[track_caller]
+fn exit_quiet(exit_code: impl IntoExitCode) -> ! {
+    exit_quiet_dyn(exit_code.into_exit_code())
+}
+
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn exit_quiet_dyn(exit_code: i32) -> ! {
+    exit(DieInfo::new(None, exit_code))
+}
+
+/// Runs the die hook then exits, or unwinds with the `panic` feature, every exit in this crate
+/// funnels into this
+#[cfg(feature = "std")]
+#[cold]
+#[inline(never)]
+fn exit(info: DieInfo) -> ! {
+    report(info, |info| {
+        #[cfg(feature = "panic")]
+        unwind::unwind(info);
+        #[cfg(not(feature = "panic"))]
+        if config::no_exit() {
+            unwind::unwind(info);
+        }
+        #[cfg(not(feature = "panic"))]
+        if info.abort {
+            std::process::abort();
+        }
+        #[cfg(all(feature = "signal", unix, not(feature = "panic")))]
+        if let Some(signal) = info.signal {
+            signal::raise(signal);
+        }
+        // process::exit isn't supported there
+        #[cfg(all(
+            feature = "wasm",
+            target_arch = "wasm32",
+            target_os = "unknown",
+            not(feature = "panic")
+        ))]
+        integration::wasm::exit(info);
+        #[cfg(not(any(
+            feature = "panic",
+            all(feature = "wasm", target_arch = "wasm32", target_os = "unknown")
+        )))]
+        std::process::exit(info.code())
+    })
+}
+
+/// Everything dying does short of terminating: decorates the message, runs the die hook, the
+/// integrations and the cleanups, then hands the finished info to `terminate`
+#[cfg(feature = "std")]
+fn report<R>(info: DieInfo, terminate: impl FnOnce(&mut DieInfo) -> R) -> R {
+    let message = info.message.map(|message| message::Message {
+        message,
+        location: info.location,
+    });
+    let mut info = DieInfo {
+        message: message.as_ref().map(|m| m as &dyn fmt::Display),
+        ..info
+    };
+    // held until everything die prints itself is out, so other threads' output can't land in the
+    // middle of it, while this thread can still write to stderr as the lock is reentrant, let go
+    // before the integrations and cleanups which may wait on threads that write to stderr
+    let stderr = ::std::io::stderr().lock();
+    info.code = exit_code::check(exit_code::remap(info.code));
+    info.stdout = output::take_stdout();
+    info.abort = abort::take_abort();
+    #[cfg(all(feature = "signal", unix))]
+    {
+        info.signal = signal::take_signal();
+    }
+    info.payload = payload::take_payload();
+    // a die caught by die::catch() is only unwound from, the process goes on
+    #[cfg(feature = "panic")]
+    if unwind::catching() {
+        drop(stderr);
+        return terminate(&mut info);
+    }
+    info.backtrace = config::capture_backtrace();
+    output::flush_stdout();
+    hook::call(&info);
+    crash_report::write(&info);
+    log_file::write(&info);
+    crash_log::write(&info);
+    drop(stderr);
+    integration::before_exit(&info);
+    #[cfg(feature = "tokio")]
+    shutdown::run();
+    cleanup::run();
+    let delay = config::exit_delay();
+    if !delay.is_zero() && !unwind::unwinds() {
+        ::std::thread::sleep(delay);
+    }
+    pause::run(&info);
+    terminate(&mut info)
+}
+
+/// Writes through the sink then terminates with the exit function set by the user, without the
+/// `std` feature
+#[cfg(not(feature = "std"))]
+#[cold]
+#[inline(never)]
+fn exit(info: DieInfo) -> ! {
+    nostd::exit(&info)
+}
+
+pub trait PrintExit {
+    fn print_exit(&self) -> !;
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for C {
+    #[inline]
+    #[track_caller]
     fn print_exit(&self) -> ! {
-        process::exit(*self)
+        exit(DieInfo::new(None, self.into_exit_code()))
     }
 }
 
 impl PrintExit for &str {
     #[inline]
+    #[track_caller]
     fn print_exit(&self) -> ! {
-        eprintln!("{}", self);
-        process::exit(DEFAULT_EXIT_CODE)
+        print_exit(DefaultExitCode, self)
     }
 }
 
+#[cfg(feature = "std")]
 impl PrintExit for String {
     #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        print_exit(DefaultExitCode, self)
+    }
+}
+
+impl PrintExit for fmt::Arguments<'_> {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        print_exit(DefaultExitCode, self)
+    }
+}
+
+impl<C: IntoExitCode + Copy> PrintExit for (C, &str) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        print_exit(self.0, self.1)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C: IntoExitCode + Copy> PrintExit for (C, String) {
+    #[inline]
+    #[track_caller]
     fn print_exit(&self) -> ! {
-        eprintln!("{}", self);
-        process::exit(DEFAULT_EXIT_CODE)
+        print_exit(self.0, &self.1)
     }
 }
 
-impl PrintExit for (i32, &str) {
+impl<C: IntoExitCode + Copy> PrintExit for (&str, C) {
     #[inline]
+    #[track_caller]
     fn print_exit(&self) -> ! {
-        eprintln!("{}", self.1);
-        process::exit(self.0)
+        print_exit(self.1, self.0)
     }
 }
 
-impl PrintExit for (i32, String) {
+#[cfg(feature = "std")]
+impl<C: IntoExitCode + Copy> PrintExit for (String, C) {
     #[inline]
+    #[track_caller]
     fn print_exit(&self) -> ! {
-        eprintln!("{}", self.1);
-        process::exit(self.0)
+        print_exit(self.1, &self.0)
     }
 }
 
-impl PrintExit for (&str, i32) {
+impl<C: IntoExitCode + Copy> PrintExit for (C, fmt::Arguments<'_>) {
     #[inline]
+    #[track_caller]
     fn print_exit(&self) -> ! {
-        eprintln!("{}", self.0);
-        process::exit(self.1)
+        print_exit(self.0, self.1)
     }
 }
 
-impl PrintExit for (String, i32) {
+impl<C: IntoExitCode + Copy> PrintExit for (fmt::Arguments<'_>, C) {
     #[inline]
+    #[track_caller]
     fn print_exit(&self) -> ! {
-        eprintln!("{}", self.0);
-        process::exit(self.1)
+        print_exit(self.1, self.0)
     }
 }
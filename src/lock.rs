@@ -0,0 +1,98 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::sync::{LockResult, TryLockError, TryLockResult};
+
+use exit_code::DefaultExitCode;
+use {print_exit, IntoExitCode};
+
+/// `DieLock` is a trait implemented on the results of locking a [`Mutex`] or [`RwLock`], to exit
+/// with a message saying why the lock couldn't be taken
+///
+/// [`Die`] works on them too, but can't tell a poisoned lock from any other error.
+///
+/// [`Mutex`]: https://doc.rust-lang.org/std/sync/struct.Mutex.html
+/// [`RwLock`]: https://doc.rust-lang.org/std/sync/struct.RwLock.html
+/// [`Die`]: trait.Die.html
+pub trait DieLock<G> {
+    /// Unwraps a lock result, yielding the guard.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(1)][exit] if the lock is poisoned, after printing the passed message
+    /// followed by `: poisoned by a thread that panicked while holding it` to [`stderr`], or for
+    /// a `try_lock` that would block, `: held by another thread`.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// # use die::DieLock;
+    /// use std::sync::{Arc, Mutex};
+    /// let state = Arc::new(Mutex::new(0));
+    /// let poisoner = state.clone();
+    /// let _ = std::thread::spawn(move || {
+    ///     let _guard = poisoner.lock().unwrap();
+    ///     panic!("oops");
+    /// })
+    /// .join();
+    /// state.lock().die_lock("state"); // prints `state: poisoned by a thread that panicked while holding it` to stderr then exits with code 1
+    /// ```
+    ///
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    fn die_lock(self, msg: impl fmt::Display) -> G;
+
+    /// Unwraps a lock result, yielding the guard.
+    ///
+    /// # Exits
+    ///
+    /// Calls [process::exit(exit_code)][exit] if the lock couldn't be taken, after printing the
+    /// same message as [`die_lock`].
+    ///
+    /// [exit]: https://doc.rust-lang.org/std/process/fn.exit.html
+    /// [`die_lock`]: trait.DieLock.html#tymethod.die_lock
+    fn die_lock_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> G;
+}
+
+const POISONED: &str = "poisoned by a thread that panicked while holding it";
+
+impl<G> DieLock<G> for LockResult<G> {
+    #[inline]
+    #[track_caller]
+    fn die_lock(self, msg: impl fmt::Display) -> G {
+        self.die_lock_code(msg, DefaultExitCode)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_lock_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> G {
+        match self {
+            Ok(guard) => guard,
+            Err(_) => print_exit(exit_code, format_args!("{}: {}", msg, POISONED)),
+        }
+    }
+}
+
+impl<G> DieLock<G> for TryLockResult<G> {
+    #[inline]
+    #[track_caller]
+    fn die_lock(self, msg: impl fmt::Display) -> G {
+        self.die_lock_code(msg, DefaultExitCode)
+    }
+    #[inline]
+    #[track_caller]
+    fn die_lock_code(self, msg: impl fmt::Display, exit_code: impl IntoExitCode) -> G {
+        match self {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(_)) => {
+                print_exit(exit_code, format_args!("{}: {}", msg, POISONED))
+            }
+            Err(TryLockError::WouldBlock) => {
+                print_exit(exit_code, format_args!("{}: held by another thread", msg))
+            }
+        }
+    }
+}
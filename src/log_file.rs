@@ -0,0 +1,59 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{PoisonError, RwLock};
+use std::time::SystemTime;
+
+use encode::Timestamp;
+use DieInfo;
+
+static PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Appends a line for every die to the file at `path` too, creating it if needed, so daemons
+/// whose stderr goes to `/dev/null` still leave a record of why they exited
+///
+/// Each line has a timestamp, the exit code and the message, if there is one, like
+/// `2024-05-01T12:34:56.789Z code=1 corrupt index`. This happens even when stderr is silenced
+/// with [`set_quiet`], and failing to write the file is ignored.
+///
+/// [`set_quiet`]: fn.set_quiet.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_log_file("/tmp/myprog.log");
+/// die!("corrupt index"); // prints `corrupt index`, appends `<timestamp> code=1 corrupt index` to /tmp/myprog.log, then exits with code 1
+/// ```
+pub fn set_log_file(path: impl Into<PathBuf>) {
+    *PATH.write().unwrap_or_else(PoisonError::into_inner) = Some(path.into());
+}
+
+/// Appends `info` to the log file, if one is set
+pub(crate) fn write(info: &DieInfo) {
+    let path = PATH.read().unwrap_or_else(PoisonError::into_inner);
+    let path = match *path {
+        Some(ref path) => path,
+        None => return,
+    };
+    let file = OpenOptions::new().create(true).append(true).open(path);
+    if let Ok(mut file) = file {
+        let line = match info.message() {
+            Some(message) => format!(
+                "{} code={} {}\n",
+                Timestamp(SystemTime::now()),
+                info.code(),
+                message
+            ),
+            None => format!("{} code={}\n", Timestamp(SystemTime::now()), info.code()),
+        };
+        // a single write, so concurrent processes appending to the same file don't interleave
+        let _ = file.write_all(line.as_bytes());
+    }
+}
@@ -0,0 +1,35 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::time::SystemTime;
+
+use encode::{Str, Timestamp};
+use DieInfo;
+
+/// Writes `info` as a single logfmt line, `level=fatal code=1 msg="..." ts=...`, with a
+/// `backtrace` too if one was captured
+pub(crate) fn write(out: &mut dyn Write, info: &DieInfo) -> io::Result<()> {
+    write!(out, "level=fatal code={}", info.code())?;
+    if let Some(message) = info.message() {
+        write!(out, " msg={}", Str(&message.to_string()))?;
+    }
+    write!(out, " ts={}", Timestamp(SystemTime::now()))?;
+    if let Some(backtrace) = info.backtrace() {
+        write!(out, " backtrace={}", Str(&backtrace.to_string()))?;
+    }
+    writeln!(out)
+}
+
+pub(crate) fn write_warning(out: &mut dyn Write, message: &dyn fmt::Display) -> io::Result<()> {
+    writeln!(
+        out,
+        "level=warn msg={} ts={}",
+        Str(&message.to_string()),
+        Timestamp(SystemTime::now())
+    )
+}
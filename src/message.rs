@@ -0,0 +1,140 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(feature = "color")]
+use std::env;
+use std::fmt;
+use std::panic::Location;
+use std::thread;
+use std::time::SystemTime;
+
+use config;
+#[cfg(feature = "color")]
+use config::Color;
+use encode::Timestamp;
+#[cfg(feature = "i18n")]
+use i18n;
+
+/// Renders a die message the way it is configured, prefixed with the program name and with the
+/// location it died at when enabled, Perl style or not
+pub(crate) struct Message<'a> {
+    pub(crate) message: &'a dyn fmt::Display,
+    pub(crate) location: &'a Location<'a>,
+}
+
+impl fmt::Display for Message<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "i18n")]
+        let translated = i18n::translate(self.message);
+        #[cfg(feature = "i18n")]
+        let message = match translated {
+            Some(ref translated) => translated as &dyn fmt::Display,
+            None => self.message,
+        };
+        #[cfg(not(feature = "i18n"))]
+        let message = self.message;
+        if let Some(program) = config::program_prefix() {
+            write!(f, "{}: ", program)?;
+        }
+        if config::thread_name() {
+            let thread = thread::current();
+            match thread.name() {
+                Some("main") => {}
+                Some(name) => write!(f, "thread '{}': ", name)?,
+                None => write!(f, "thread {:?}: ", thread.id())?,
+            }
+        }
+        if config::perl_style() {
+            // the trailing newline is printed by whatever prints this, like eprintln!()
+            let message = message.to_string();
+            return match message.strip_suffix('\n') {
+                Some(message) => f.write_str(message),
+                None => write!(
+                    f,
+                    "{} at {} line {}.",
+                    message,
+                    self.location.file(),
+                    self.location.line()
+                ),
+            };
+        }
+        message.fmt(f)?;
+        if config::location() {
+            write!(f, " (at {}:{})", self.location.file(), self.location.line())?;
+        }
+        Ok(())
+    }
+}
+
+/// The timestamp messages start with when enabled with [`set_timestamp`], followed by a space
+///
+/// [`set_timestamp`]: ../fn.set_timestamp.html
+pub(crate) struct TimestampPrefix(Option<SystemTime>);
+
+impl TimestampPrefix {
+    pub(crate) fn now() -> TimestampPrefix {
+        TimestampPrefix(Some(SystemTime::now()).filter(|_| config::timestamp()))
+    }
+
+    /// How many columns it takes up
+    #[cfg(feature = "wrap")]
+    pub(crate) fn width(&self) -> usize {
+        self.0.map_or(0, |_| "2024-05-01T12:34:56.789Z ".len())
+    }
+}
+
+impl fmt::Display for TimestampPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(time) => write!(f, "{} ", Timestamp(time)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The `error: ` label the default hook starts messages with, if enabled, colored when asked to
+/// or writing to a `terminal`
+pub(crate) fn error_label(terminal: bool) -> &'static str {
+    if !config::error_label() {
+        ""
+    } else if color(terminal) {
+        "\x1b[1;31merror:\x1b[0m "
+    } else {
+        "error: "
+    }
+}
+
+/// The `warning: ` label [`warn`]!() starts messages with, enabled along with the `error: ` one,
+/// colored bold yellow under the same conditions
+///
+/// [`warn`]: ../macro.warn.html
+pub(crate) fn warning_label(terminal: bool) -> &'static str {
+    if !config::error_label() {
+        ""
+    } else if color(terminal) {
+        "\x1b[1;33mwarning:\x1b[0m "
+    } else {
+        "warning: "
+    }
+}
+
+#[cfg(feature = "color")]
+fn color(terminal: bool) -> bool {
+    match config::color() {
+        Color::Always => true,
+        Color::Never => false,
+        Color::Auto => {
+            env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                && env::var_os("CLICOLOR").is_none_or(|v| v != "0")
+                && terminal
+        }
+    }
+}
+
+#[cfg(not(feature = "color"))]
+fn color(_terminal: bool) -> bool {
+    false
+}
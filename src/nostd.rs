@@ -0,0 +1,66 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Without the `std` feature there is no stderr to print to and no process to exit, so both are
+//! supplied by the user
+
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use DieInfo;
+
+static SINK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+static EXIT: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Sets the function every die is written through without the `std` feature, like a UART writer
+/// or `libc::write` to fd 2 on hosted targets, nothing is written until one is set
+///
+/// # Examples
+///
+/// ```ignore
+/// fn sink(info: &die::DieInfo) {
+///     let _ = writeln!(uart(), "{}", info);
+/// }
+/// die::set_sink(sink);
+/// ```
+pub fn set_sink(sink: fn(&DieInfo)) {
+    SINK.store(sink as *mut (), Ordering::Release);
+}
+
+/// Sets the function terminating the program after a die without the `std` feature, like
+/// `libc::_exit` on hosted targets or a reset on embedded ones
+///
+/// Until one is set, dying panics with the exit code, leaving it to the panic handler.
+///
+/// # Examples
+///
+/// ```ignore
+/// fn exit(code: i32) -> ! {
+///     unsafe { libc::_exit(code) }
+/// }
+/// die::set_exit(exit);
+/// ```
+pub fn set_exit(exit: fn(i32) -> !) {
+    EXIT.store(exit as *mut (), Ordering::Release);
+}
+
+/// Writes `info` through the sink then terminates with the exit function
+pub(crate) fn exit(info: &DieInfo) -> ! {
+    let sink = SINK.load(Ordering::Acquire);
+    if !sink.is_null() {
+        // only ever stored from a fn(&DieInfo) in set_sink
+        let sink: fn(&DieInfo) = unsafe { mem::transmute(sink) };
+        sink(info);
+    }
+    let exit = EXIT.load(Ordering::Acquire);
+    if !exit.is_null() {
+        // only ever stored from a fn(i32) -> ! in set_exit
+        let exit: fn(i32) -> ! = unsafe { mem::transmute(exit) };
+        exit(info.code());
+    }
+    panic!("died with exit code {}", info.code())
+}
@@ -0,0 +1,147 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::cell::{Cell, RefCell};
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Mutex, PoisonError, TryLockError};
+
+use config;
+
+type Output = Box<dyn Write + Send>;
+
+static OUTPUT: Mutex<Option<Output>> = Mutex::new(None);
+
+thread_local! {
+    static THREAD_OUTPUT: RefCell<Option<Output>> = const { RefCell::new(None) };
+    static STDOUT: Cell<bool> = const { Cell::new(false) };
+    static WRITING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sets where the default hook writes messages instead of [`stderr`], like a file, a pipe or an
+/// in-memory buffer, returning the previously set one
+///
+/// A writer set with [`set_thread_output`] takes precedence on its thread. Write errors are
+/// ignored, the process is dying anyway.
+///
+/// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+/// [`set_thread_output`]: fn.set_thread_output.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_output(Box::new(std::io::stdout()));
+/// die!("no such file"); // prints `no such file` to stdout then exits with code 1
+/// ```
+pub fn set_output(output: Box<dyn Write + Send>) -> Option<Box<dyn Write + Send>> {
+    OUTPUT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .replace(output)
+}
+
+/// Unsets the writer set with [`set_output`], returning it, so messages go to [`stderr`] again
+///
+/// [`set_output`]: fn.set_output.html
+/// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+pub fn take_output() -> Option<Box<dyn Write + Send>> {
+    OUTPUT.lock().unwrap_or_else(PoisonError::into_inner).take()
+}
+
+/// Like [`set_output`], but only for dies on the current thread
+///
+/// [`set_output`]: fn.set_output.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// die::set_thread_output(Box::new(std::io::stdout()));
+/// die!("no such file"); // prints `no such file` to stdout then exits with code 1
+/// ```
+pub fn set_thread_output(output: Box<dyn Write + Send>) -> Option<Box<dyn Write + Send>> {
+    THREAD_OUTPUT.with(|o| o.borrow_mut().replace(output))
+}
+
+/// Unsets the writer set with [`set_thread_output`] on the current thread, returning it
+///
+/// [`set_thread_output`]: fn.set_thread_output.html
+pub fn take_thread_output() -> Option<Box<dyn Write + Send>> {
+    THREAD_OUTPUT.with(|o| o.borrow_mut().take())
+}
+
+/// Makes the next die on this thread print to stdout, for die_out!()
+pub fn to_stdout() {
+    STDOUT.with(|stdout| stdout.set(true));
+}
+
+/// Whether the die in progress on this thread prints to stdout, resetting it for the next one
+pub(crate) fn take_stdout() -> bool {
+    STDOUT.with(|stdout| stdout.replace(false))
+}
+
+/// Flushes stdout if enabled, keeping it locked until the process exits if enabled
+pub(crate) fn flush_stdout() {
+    if !config::flush_stdout() {
+        return;
+    }
+    let mut stdout = io::stdout().lock();
+    let _ = stdout.flush();
+    // the lock is reentrant, so this thread can still write to it, like die_out!() does
+    #[cfg(not(feature = "panic"))]
+    if config::lock_stdout() && !config::no_exit() {
+        ::std::mem::forget(stdout);
+    }
+}
+
+/// Calls `f` with locked stdout if `stdout`, or else the writer set for this thread, or the
+/// global one, or locked stderr, along with whether that is a terminal
+pub(crate) fn with(stdout: bool, f: impl FnOnce(&mut dyn Write, bool)) {
+    if stdout {
+        let stdout = io::stdout();
+        let terminal = stdout.is_terminal();
+        return f(&mut stdout.lock(), terminal);
+    }
+    let mut f = Some(f);
+    THREAD_OUTPUT.with(|o| {
+        // a writer dying while it's borrowed falls through to the next one
+        if let Ok(mut o) = o.try_borrow_mut() {
+            if let Some(ref mut o) = *o {
+                (f.take().unwrap())(o, false);
+            }
+        }
+    });
+    let f = match f {
+        Some(f) => f,
+        None => return,
+    };
+    // a writer dying while this thread holds the lock falls through to stderr instead of
+    // deadlocking on it, another thread holding it is waited for
+    let output = match OUTPUT.try_lock() {
+        Ok(output) => Some(output),
+        Err(TryLockError::Poisoned(err)) => Some(err.into_inner()),
+        Err(TryLockError::WouldBlock) if WRITING.with(Cell::get) => None,
+        Err(TryLockError::WouldBlock) => {
+            Some(OUTPUT.lock().unwrap_or_else(PoisonError::into_inner))
+        }
+    };
+    if let Some(mut output) = output {
+        if let Some(ref mut o) = *output {
+            struct Writing;
+            impl Drop for Writing {
+                fn drop(&mut self) {
+                    WRITING.with(|writing| writing.set(false));
+                }
+            }
+            WRITING.with(|writing| writing.set(true));
+            let _writing = Writing;
+            return f(o, false);
+        }
+    }
+    let stderr = io::stderr();
+    let terminal = stderr.is_terminal();
+    f(&mut stderr.lock(), terminal)
+}
@@ -0,0 +1,63 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::panic::{self, PanicHookInfo};
+
+use {exit, report, unwind, DieInfo, IntoExitCode};
+
+/// Installs a panic hook that dies instead, so panics go through the same pipeline as [`die`]!()
+/// and exit with `code`, replacing the current panic hook
+///
+/// The message is `panicked: ` followed by the panic message, decorated like every other message
+/// with the program prefix, the location of the panic when enabled with [`set_location`], color,
+/// JSON, the die hook and the cleanups. Rust itself uses `101` for panics.
+///
+/// With the `panic` feature, or [`set_no_exit`], the panic keeps unwinding once the message is
+/// printed, dying from inside a panic would abort.
+///
+/// [`die`]: macro.die.html
+/// [`set_location`]: fn.set_location.html
+/// [`set_no_exit`]: fn.set_no_exit.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// die::install_die_panic_hook(101);
+/// let args: Vec<String> = Vec::new();
+/// let _ = &args[1]; // prints `panicked: index out of bounds: the len is 0 but the index is 1` then exits with code 101
+/// ```
+pub fn install_die_panic_hook(code: impl IntoExitCode) {
+    let code = code.into_exit_code();
+    panic::set_hook(Box::new(move |panic| {
+        let message = Panicked(panic);
+        let mut info = DieInfo::new(Some(&message), code);
+        if let Some(location) = panic.location() {
+            info.location = location;
+        }
+        if unwind::unwinds() {
+            report(info, |_| ());
+        } else {
+            exit(info);
+        }
+    }));
+}
+
+struct Panicked<'a>(&'a PanicHookInfo<'a>);
+
+impl fmt::Display for Panicked<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let payload = self.0.payload();
+        match (
+            payload.downcast_ref::<&str>(),
+            payload.downcast_ref::<String>(),
+        ) {
+            (Some(message), _) => write!(f, "panicked: {}", message),
+            (_, Some(message)) => write!(f, "panicked: {}", message),
+            _ => f.write_str("panicked"),
+        }
+    }
+}
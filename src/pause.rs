@@ -0,0 +1,48 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use {config, unwind, DieInfo};
+
+/// Waits for Enter if enabled and the console would close with the process, see
+/// [`config::set_pause`]
+///
+/// [`config::set_pause`]: ../fn.set_pause.html
+pub(crate) fn run(info: &DieInfo) {
+    if !config::pause()
+        || info.message().is_none()
+        || config::quiet()
+        || unwind::unwinds()
+        || !io::stdin().is_terminal()
+        || !io::stderr().is_terminal()
+        || !console_closes()
+    {
+        return;
+    }
+    let mut stderr = io::stderr().lock();
+    let _ = write!(stderr, "press Enter to exit");
+    let _ = stderr.flush();
+    drop(stderr);
+    let _ = io::stdin().lock().read_line(&mut String::new());
+}
+
+/// Whether the console goes away with this process, as nothing else is attached to it
+#[cfg(windows)]
+fn console_closes() -> bool {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetConsoleProcessList(list: *mut u32, count: u32) -> u32;
+    }
+    let mut list = [0u32; 2];
+    unsafe { GetConsoleProcessList(list.as_mut_ptr(), list.len() as u32) == 1 }
+}
+
+/// There is no telling off windows, so any terminal is assumed to
+#[cfg(not(windows))]
+fn console_closes() -> bool {
+    true
+}
@@ -0,0 +1,61 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt;
+
+use {print_exit, IntoExitCode};
+
+thread_local! {
+    static PAYLOAD: RefCell<Option<Box<dyn Any + Send>>> = const { RefCell::new(None) };
+}
+
+/// Attaches `payload` to the next die on this thread, for die!(payload = ...)
+pub fn set_payload(payload: Box<dyn Any + Send>) {
+    PAYLOAD.with(|p| *p.borrow_mut() = Some(payload));
+}
+
+/// The payload of the die in progress on this thread, resetting it for the next one
+pub(crate) fn take_payload() -> Option<Box<dyn Any + Send>> {
+    PAYLOAD.with(|p| p.borrow_mut().take())
+}
+
+/// Prints `msg` then exits with `exit_code`, like [`die`]!(), carrying a machine readable
+/// `payload`, like Perl's `die $object`
+///
+/// Hooks get it from [`DieInfo::payload`], and [`catch`] from [`Died::payload`], to act on the
+/// details of an error without parsing its message. [`die`]!() takes one too, with
+/// `die!(payload = value; ...)`.
+///
+/// [`die`]: macro.die.html
+/// [`DieInfo::payload`]: struct.DieInfo.html#method.payload
+/// [`catch`]: fn.catch.html
+/// [`Died::payload`]: struct.Died.html#method.payload
+///
+/// # Examples
+///
+/// ```should_panic
+/// struct Conflict {
+///     path: &'static str,
+/// }
+///
+/// die::set_hook(Box::new(|info| {
+///     if let Some(conflict) = info.payload().and_then(|p| p.downcast_ref::<Conflict>()) {
+///         eprintln!("{{\"conflict\":\"{}\"}}", conflict.path);
+///     }
+/// }));
+/// die::die_with_payload(3, "merge conflict", Conflict { path: "src/lib.rs" }); // prints `{"conflict":"src/lib.rs"}` then exits with code 3
+/// ```
+#[track_caller]
+pub fn die_with_payload(
+    exit_code: impl IntoExitCode,
+    msg: impl fmt::Display,
+    payload: impl Any + Send,
+) -> ! {
+    set_payload(Box::new(payload));
+    print_exit(exit_code, msg)
+}
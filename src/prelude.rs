@@ -0,0 +1,41 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The traits, macros and exit codes binaries usually need, in one import
+//!
+//! ```should_panic
+//! use die::prelude::*;
+//! let port: Option<u16> = None;
+//! let port = port.die_code("no port given", codes::EX_USAGE);
+//! die!("cannot listen on {}", port); // never reached, the line above prints `no port given` to stderr then exits with code 64
+//! ```
+//!
+//! The `warn` macro is left out, a glob import of it would make the built in `#[warn]` attribute
+//! ambiguous, the [`Warn`] trait is in.
+//!
+//! [`Warn`]: ../trait.Warn.html
+
+#[doc(no_inline)]
+pub use codes;
+#[cfg(all(feature = "signal", unix))]
+#[doc(no_inline)]
+pub use die_signal;
+#[cfg(feature = "clap")]
+#[doc(no_inline)]
+pub use DieClap;
+#[cfg(feature = "serde_json")]
+#[doc(no_inline)]
+pub use DieJson;
+#[doc(no_inline)]
+pub use {die, die_if, die_unless, ensure_or_die, or_die};
+#[cfg(feature = "std")]
+#[doc(no_inline)]
+pub use {die_abort, die_out, die_usage};
+#[doc(no_inline)]
+pub use {Die, DieCode, DieResult, ExitCode, ExitCodeFromError, IntoExitCode};
+#[cfg(feature = "std")]
+#[doc(no_inline)]
+pub use {DieBoxed, DieIo, DieIter, DieLock, Warn};
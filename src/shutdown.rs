@@ -0,0 +1,108 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use tokio::runtime::{Builder, Handle, RuntimeFlavor};
+use tokio::task;
+
+type Shutdown = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+static SHUTDOWNS: Mutex<Vec<Shutdown>> = Mutex::new(Vec::new());
+static TIMEOUT_MS: AtomicU64 = AtomicU64::new(5000);
+
+/// Registers `future` to run to completion right before the process dies, the async
+/// [`on_exit`]
+///
+/// [`process::exit`] strands every task still running on the tokio runtime, so flushing a
+/// buffered writer, closing connections or telling background tasks to stop goes here instead.
+/// The futures run on the current runtime if the die happened inside one, or a fresh one
+/// otherwise, one at a time, last registered first, all together for at most the time set with
+/// [`set_shutdown_timeout`]. They run after the die hook and before the [`on_exit`] cleanups.
+///
+/// Only the thread inside `Runtime::block_on` drives the IO and timer drivers of a
+/// current_thread runtime, and that thread is busy dying, so there futures waiting on IO or
+/// timers never finish and only hold up the exit until the timeout.
+///
+/// [`on_exit`]: fn.on_exit.html
+/// [`process::exit`]: https://doc.rust-lang.org/std/process/fn.exit.html
+/// [`set_shutdown_timeout`]: fn.set_shutdown_timeout.html
+///
+/// # Examples
+///
+/// ```should_panic,edition2018
+/// # use die::die;
+/// die::on_exit_async(async {
+///     println!("connections closed");
+/// });
+/// die!("shutting down"); // prints `shutting down`, then `connections closed`, then exits with code 1
+/// ```
+pub fn on_exit_async(future: impl Future<Output = ()> + Send + 'static) {
+    SHUTDOWNS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Box::pin(future));
+}
+
+/// Sets how long the futures registered with [`on_exit_async`] get to finish, all together,
+/// before the process exits anyway, 5 seconds by default
+///
+/// [`on_exit_async`]: fn.on_exit_async.html
+pub fn set_shutdown_timeout(timeout: Duration) {
+    TIMEOUT_MS.store(timeout.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// Runs the registered futures, last registered first, on another thread so this works both
+/// inside and outside a runtime, waiting at most the shutdown timeout for them
+pub(crate) fn run() {
+    let shutdowns = {
+        let mut shutdowns = SHUTDOWNS.lock().unwrap_or_else(PoisonError::into_inner);
+        if shutdowns.is_empty() {
+            return;
+        }
+        shutdowns.drain(..).collect::<Vec<_>>()
+    };
+    let handle = Handle::try_current().ok();
+    let worker = handle
+        .as_ref()
+        .is_some_and(|handle| handle.runtime_flavor() == RuntimeFlavor::MultiThread);
+    let (done, finished) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name("die-shutdown".into())
+        .spawn(move || {
+            let runtime;
+            let handle = match handle {
+                Some(handle) => handle,
+                None => match Builder::new_current_thread().enable_all().build() {
+                    Ok(built) => {
+                        runtime = built;
+                        runtime.handle().clone()
+                    }
+                    Err(_) => return,
+                },
+            };
+            for shutdown in shutdowns.into_iter().rev() {
+                handle.block_on(shutdown);
+            }
+            let _ = done.send(());
+        });
+    if spawned.is_ok() {
+        let timeout = Duration::from_millis(TIMEOUT_MS.load(Ordering::Relaxed));
+        let wait = || {
+            let _ = finished.recv_timeout(timeout);
+        };
+        // hand this worker's tasks and drivers to another thread while it waits
+        match worker {
+            true => task::block_in_place(wait),
+            false => wait(),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::cell::Cell;
+
+thread_local! {
+    static SIGNAL: Cell<Option<i32>> = const { Cell::new(None) };
+}
+
+/// Makes the next die on this thread raise `signal` instead of exiting, for die_signal!()
+pub fn to_signal(signal: i32) {
+    SIGNAL.with(|s| s.set(Some(signal)));
+}
+
+/// The signal the die in progress on this thread raises, resetting it for the next one
+pub(crate) fn take_signal() -> Option<i32> {
+    SIGNAL.with(|s| s.take())
+}
+
+/// Raises `signal` with its default disposition and unblocked, so it kills the process, only
+/// returning if it doesn't, like for `SIGCHLD`
+#[cfg(not(feature = "panic"))]
+pub(crate) fn raise(signal: i32) {
+    use libc;
+    use std::mem;
+
+    unsafe {
+        libc::signal(signal, libc::SIG_DFL);
+        let mut set: libc::sigset_t = mem::zeroed();
+        libc::sigemptyset(&mut set);
+        libc::sigaddset(&mut set, signal);
+        libc::pthread_sigmask(libc::SIG_UNBLOCK, &set, ::std::ptr::null_mut());
+        libc::raise(signal);
+    }
+}
@@ -0,0 +1,111 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::fmt::{self, Display, Write};
+
+use exit_code::DefaultExitCode;
+use {print_exit, IntoExitCode, PrintExit};
+
+/// Renders `message` followed by line `line` of `source` with a caret under column `column`, like
+/// a compiler points at a mistake, for the fatal errors of config and DSL parsers
+///
+/// Lines and columns count from 1, columns in characters, and a column past the end of the line
+/// points just after it. Only the message is printed if `source` has no such line.
+///
+/// The result is displayed like any other message, or can be passed to [`die`]!() directly.
+/// [`Diagnostic::snippet`] adds the same to a [`fatal`] diagnostic.
+///
+/// [`die`]: macro.die.html
+/// [`Diagnostic::snippet`]: struct.Diagnostic.html#method.snippet
+/// [`fatal`]: fn.fatal.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die;
+/// let source = "name = \"die\"\nport 8080\n";
+/// die!(die::snippet(source, 2, 6, "expected `=` after `port`"); 65);
+/// // prints
+/// // expected `=` after `port`
+/// //   |
+/// // 2 | port 8080
+/// //   |      ^
+/// // to stderr then exits with code 65
+/// ```
+pub fn snippet<M: Display>(source: &str, line: usize, column: usize, message: M) -> Snippet<'_, M> {
+    Snippet {
+        message,
+        line: line
+            .checked_sub(1)
+            .and_then(|i| source.lines().nth(i))
+            .map(|text| (line, text)),
+        column,
+    }
+}
+
+/// A message with the offending line of its source, built with [`snippet`]
+///
+/// [`snippet`]: fn.snippet.html
+#[derive(Debug, Clone, Copy)]
+pub struct Snippet<'a, M> {
+    message: M,
+    line: Option<(usize, &'a str)>,
+    column: usize,
+}
+
+impl<M: Display> Display for Snippet<'_, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.message.fmt(f)?;
+        match self.line {
+            Some((line, text)) => excerpt(f, line, text, self.column),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes line number `line`, `text`, under a `|` gutter and with a caret under the 1 based
+/// character `column`, each on a new line
+pub(crate) fn excerpt(f: &mut dyn Write, line: usize, text: &str, column: usize) -> fmt::Result {
+    let width = line.checked_ilog10().unwrap_or(0) as usize + 1;
+    write!(
+        f,
+        "\n{:w$} |\n{} | {}\n{:w$} | ",
+        "",
+        line,
+        text,
+        "",
+        w = width
+    )?;
+    // tabs are repeated so the caret lines up with the text above whatever their width
+    for c in text.chars().take(column.saturating_sub(1)) {
+        f.write_char(if c == '\t' { '\t' } else { ' ' })?;
+    }
+    f.write_char('^')
+}
+
+impl<M: Display> PrintExit for Snippet<'_, M> {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        print_exit(DefaultExitCode, self)
+    }
+}
+
+impl<M: Display, C: IntoExitCode + Copy> PrintExit for (C, Snippet<'_, M>) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        print_exit(self.0, &self.1)
+    }
+}
+
+impl<M: Display, C: IntoExitCode + Copy> PrintExit for (Snippet<'_, M>, C) {
+    #[inline]
+    #[track_caller]
+    fn print_exit(&self) -> ! {
+        print_exit(self.1, &self.0)
+    }
+}
@@ -0,0 +1,54 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// Finds the candidate closest to a mistyped `input`, for `did you mean 'install'?` messages
+///
+/// Closeness is the edit distance, the number of characters inserted, removed or replaced, and
+/// only candidates within a third of the input's length, at least 1, count. Ties go to the
+/// candidate that came first. There is no suggestion when the input is one of the candidates.
+///
+/// # Examples
+///
+/// ```should_panic
+/// let commands = ["install", "uninstall", "update"];
+/// let input = "instal";
+/// match die::suggest(input, commands.iter().copied()) {
+///     Some(command) => die::fatal(format_args!("unknown command `{}`", input))
+///         .help(format_args!("did you mean `{}`?", command))
+///         .exit(), // prints `unknown command `instal``, then `  = help: did you mean `install`?` then exits with code 1
+///     None => die::fatal(format_args!("unknown command `{}`", input)).exit(),
+/// }
+/// ```
+pub fn suggest<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max = (input.chars().count() / 3).max(1);
+    let mut exact = false;
+    let closest = candidates
+        .into_iter()
+        .map(|candidate| (distance(input, candidate), candidate))
+        .inspect(|&(distance, _)| exact |= distance == 0)
+        .filter(|&(distance, _)| distance <= max)
+        .min_by_key(|&(distance, _)| distance);
+    match exact {
+        true => None,
+        false => closest.map(|(_, candidate)| candidate),
+    }
+}
+
+/// Levenshtein distance, keeping a single row
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let replaced = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
@@ -0,0 +1,140 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Helpers for testing code paths that call [`die`]!() or the [`Die`] trait
+//!
+//! Dying exits the whole process, so [`assert_dies`] re-executes the current test binary in a
+//! child process, runs only the closure under test there, and asserts on the child's exit code
+//! and [`stderr`] from the parent.
+//!
+//! [`die`]: ../macro.die.html
+//! [`Die`]: ../trait.Die.html
+//! [`assert_dies`]: fn.assert_dies.html
+//! [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+
+use std::cell::Cell;
+use std::env;
+use std::process::{self, Command};
+use std::thread;
+
+const CHILD_ENV: &str = "DIE_TESTING_CHILD";
+const RETURNED: &str = "die::testing: closure returned without dying";
+
+thread_local! {
+    static CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Asserts that a closure dies with the given exit code and a [`stderr`] output containing
+/// `substr`
+///
+/// See [`assert_dies`] for details.
+///
+/// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+/// [`assert_dies`]: testing/fn.assert_dies.html
+///
+/// # Examples
+///
+/// ```
+/// # use die::{assert_dies, die};
+/// assert_dies!(3, "no config", || die!(3; "no config"));
+/// ```
+#[macro_export]
+macro_rules! assert_dies {
+    ($code:expr, $substr:expr, $f:expr) => {
+        $crate::testing::assert_dies($code, $substr, $f)
+    };
+    ($code:expr, $substr:expr, $f:expr,) => {
+        $crate::testing::assert_dies($code, $substr, $f)
+    };
+}
+
+/// Asserts that `f` dies with `code` and a [`stderr`] output containing `substr`
+///
+/// The current executable is re-run with the same test selected, in a child process where only
+/// `f` runs in place of this call and every other `assert_dies` call in the test is skipped. The
+/// test is identified by the current thread name, which the standard test harness sets to the
+/// test's name, so this must be called from the test's own thread.
+///
+/// Works with the `panic` feature enabled or `DIE_NO_EXIT` set too, the child exits with the code
+/// carried by the [`Died`] payload.
+///
+/// # Panics
+///
+/// Panics if the child could not be spawned, if `f` returns without dying, or if the exit code or
+/// [`stderr`] output don't match.
+///
+/// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+/// [`Died`]: ../struct.Died.html
+///
+/// # Examples
+///
+/// ```
+/// # use die::{die, Die};
+/// die::testing::assert_dies(2, "missing", || {
+///     let x: Option<u32> = None;
+///     x.die_code("missing", 2);
+/// });
+/// ```
+pub fn assert_dies<F: FnOnce()>(code: i32, substr: &str, f: F) {
+    let index = CALLS.with(|calls| calls.replace(calls.get() + 1));
+    let current = thread::current();
+    let test = current.name().unwrap_or("main");
+    let id = format!("{}#{}", test, index);
+
+    if let Ok(child) = env::var(CHILD_ENV) {
+        if child == id {
+            run_child(f);
+        }
+        return;
+    }
+
+    let exe = env::current_exe().expect("die::testing: cannot find current executable");
+    let mut command = Command::new(exe);
+    if test != "main" {
+        command.args([test, "--exact", "--nocapture", "--test-threads=1"]);
+    }
+    let output = command
+        .env(CHILD_ENV, &id)
+        .output()
+        .expect("die::testing: cannot spawn child process");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if stderr.contains(RETURNED) {
+        panic!(
+            "expected closure to die with code {}, but it returned",
+            code
+        );
+    }
+    if output.status.code() != Some(code) {
+        panic!(
+            "expected closure to die with code {}, but child exited with {}, stderr:\n{}",
+            code, output.status, stderr
+        );
+    }
+    if !stderr.contains(substr) {
+        panic!(
+            "expected stderr of closure dying with code {} to contain {:?}, stderr:\n{}",
+            code, substr, stderr
+        );
+    }
+}
+
+fn run_child<F: FnOnce()>(f: F) -> ! {
+    // dying unwinds instead of exiting with the panic feature or DIE_NO_EXIT
+    let payload = match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)) {
+        Ok(()) => returned(),
+        Err(payload) => payload,
+    };
+    match payload.downcast::<::Died>() {
+        Ok(died) => process::exit(died.code()),
+        Err(payload) => ::std::panic::resume_unwind(payload),
+    }
+}
+
+fn returned() -> ! {
+    eprintln!("{}", RETURNED);
+    process::exit(0)
+}
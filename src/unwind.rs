@@ -0,0 +1,214 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::any::Any;
+#[cfg(feature = "panic")]
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+use std::panic;
+#[cfg(feature = "panic")]
+use std::panic::UnwindSafe;
+
+#[cfg(not(feature = "panic"))]
+use config;
+use DieInfo;
+
+/// The panic payload [`die`]!() and the [`Die`] trait unwind with instead of exiting when the
+/// `panic` feature is enabled, or at runtime with [`set_no_exit`]
+///
+/// The feature is meant for tests: enable it in `[dev-dependencies]` and code that dies can be
+/// tested with `#[should_panic]` without the test harness itself being killed. The payload can be
+/// recovered with [`catch_unwind`] and [`downcast`] to inspect the exit code and message.
+///
+/// The die hook still runs first, so the message is printed as usual, but the default panic hook
+/// is not invoked.
+///
+/// [`die`]: macro.die.html
+/// [`Die`]: trait.Die.html
+/// [`set_no_exit`]: fn.set_no_exit.html
+/// [`catch_unwind`]: https://doc.rust-lang.org/std/panic/fn.catch_unwind.html
+/// [`downcast`]: https://doc.rust-lang.org/std/boxed/struct.Box.html#method.downcast
+///
+/// # Examples
+///
+/// ```toml
+/// [dev-dependencies]
+/// die = { version = "0.2", features = ["panic"] }
+/// ```
+///
+/// ```
+/// # #[cfg(feature = "panic")] {
+/// # use die::die;
+/// let payload = std::panic::catch_unwind(|| die!(4; "bad input")).unwrap_err();
+/// let died = payload.downcast::<die::Died>().unwrap();
+/// assert_eq!(died.code(), 4);
+/// assert_eq!(died.message(), Some("bad input"));
+/// # }
+/// ```
+pub struct Died {
+    code: i32,
+    message: Option<String>,
+    payload: Option<Box<dyn Any + Send>>,
+}
+
+impl Died {
+    /// The code the process would have exited with
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// The message that was printed, or `None` if there was only an exit code
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// The payload attached with [`die_with_payload`] or `die!(payload = ...)`
+    ///
+    /// [`die_with_payload`]: fn.die_with_payload.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "panic")] {
+    /// # use die::die;
+    /// let died = die::catch(|| die!(payload = 404u16; 2; "not found")).unwrap_err();
+    /// assert_eq!(died.payload().unwrap().downcast_ref(), Some(&404u16));
+    /// # }
+    /// ```
+    pub fn payload(&self) -> Option<&(dyn Any + Send)> {
+        self.payload.as_deref()
+    }
+
+    /// Takes the payload out, to [`downcast`] it by value
+    ///
+    /// [`downcast`]: https://doc.rust-lang.org/std/boxed/struct.Box.html#method.downcast
+    pub fn take_payload(&mut self) -> Option<Box<dyn Any + Send>> {
+        self.payload.take()
+    }
+}
+
+impl<'a> From<&'a DieInfo<'a>> for Died {
+    fn from(info: &'a DieInfo<'a>) -> Died {
+        Died {
+            code: info.code(),
+            message: info.message().map(|m| m.to_string()),
+            payload: None,
+        }
+    }
+}
+
+impl fmt::Debug for Died {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Died")
+            .field("code", &self.code)
+            .field("message", &self.message)
+            .field("payload", &self.payload)
+            .finish()
+    }
+}
+
+/// Payloads can't be cloned, so a clone doesn't carry one
+impl Clone for Died {
+    fn clone(&self) -> Died {
+        Died {
+            code: self.code,
+            message: self.message.clone(),
+            payload: None,
+        }
+    }
+}
+
+/// Payloads can't be compared, so only the code and message are
+impl PartialEq for Died {
+    fn eq(&self, other: &Died) -> bool {
+        self.code == other.code && self.message == other.message
+    }
+}
+
+impl Eq for Died {}
+
+impl fmt::Display for Died {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.message {
+            Some(ref message) => write!(f, "died with code {}: {}", self.code, message),
+            None => write!(f, "died with code {}", self.code),
+        }
+    }
+}
+
+impl Error for Died {}
+
+#[cfg(feature = "panic")]
+thread_local! {
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Runs `f`, returning what it dies with as an error instead of unwinding any further, like
+/// Perl's `eval { ... }`, with the `panic` feature
+///
+/// For REPLs, plugin hosts and test harnesses that need to recover from code that dies. A die
+/// caught this way is only unwound from: nothing is printed, and neither the die hook nor the
+/// [`on_exit`] cleanups run, as the process isn't exiting. Panics that aren't a die keep
+/// unwinding.
+///
+/// [`on_exit`]: fn.on_exit.html
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "panic")] {
+/// # use die::die;
+/// let died = die::catch(|| die!(4; "bad input")).unwrap_err();
+/// assert_eq!(died.code(), 4);
+/// assert_eq!(died.message(), Some("bad input"));
+/// assert_eq!(die::catch(|| 2 + 2), Ok(4));
+/// # }
+/// ```
+#[cfg(feature = "panic")]
+pub fn catch<T>(f: impl FnOnce() -> T + UnwindSafe) -> Result<T, Died> {
+    struct Catching;
+    impl Drop for Catching {
+        fn drop(&mut self) {
+            CATCHING.with(|catching| catching.set(catching.get() - 1));
+        }
+    }
+    CATCHING.with(|catching| catching.set(catching.get() + 1));
+    let catching = Catching;
+    let result = panic::catch_unwind(f);
+    drop(catching);
+    result.map_err(|payload| match payload.downcast::<Died>() {
+        Ok(died) => *died,
+        Err(payload) => panic::resume_unwind(payload),
+    })
+}
+
+/// Whether a die on this thread is going to be caught by [`catch`]
+///
+/// [`catch`]: fn.catch.html
+#[cfg(feature = "panic")]
+pub(crate) fn catching() -> bool {
+    CATCHING.with(|catching| catching.get() > 0)
+}
+
+/// Unwinds with a [`Died`] payload, without invoking the panic hook
+///
+/// [`Died`]: struct.Died.html
+pub(crate) fn unwind(info: &mut DieInfo) -> ! {
+    let mut died = Died::from(&*info);
+    died.payload = info.payload.take();
+    panic::resume_unwind(Box::new(died))
+}
+
+/// Whether dying unwinds instead of exiting, with the `panic` feature or [`set_no_exit`]
+///
+/// [`set_no_exit`]: fn.set_no_exit.html
+pub(crate) fn unwinds() -> bool {
+    #[cfg(feature = "panic")]
+    return true;
+    #[cfg(not(feature = "panic"))]
+    config::no_exit()
+}
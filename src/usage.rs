@@ -0,0 +1,54 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+
+use print_exit;
+
+static USAGE: RwLock<Option<String>> = RwLock::new(None);
+
+/// The exit code of [`die_usage`]!(), like clap and most of coreutils use for bad arguments
+///
+/// [`die_usage`]: macro.die_usage.html
+pub const USAGE_EXIT_CODE: i32 = 2;
+
+/// Registers the usage text [`die_usage`]!() prints after its message
+///
+/// [`die_usage`]: macro.die_usage.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// # use die::die_usage;
+/// die::set_usage("usage: myprog [-v] FILE...");
+/// die_usage!("unknown flag -q"); // prints `unknown flag -q`, a blank line, then the usage to stderr then exits with code 2
+/// ```
+pub fn set_usage(usage: impl Into<String>) {
+    *USAGE.write().unwrap_or_else(PoisonError::into_inner) = Some(usage.into());
+}
+
+#[track_caller]
+pub(crate) fn die_usage(message: Option<&dyn fmt::Display>) -> ! {
+    let usage = USAGE.read().unwrap_or_else(PoisonError::into_inner).clone();
+    print_exit(USAGE_EXIT_CODE, Usage { message, usage })
+}
+
+struct Usage<'a> {
+    message: Option<&'a dyn fmt::Display>,
+    usage: Option<String>,
+}
+
+impl fmt::Display for Usage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.message, &self.usage) {
+            (Some(message), Some(usage)) => write!(f, "{}\n\n{}", message, usage.trim_end()),
+            (Some(message), None) => message.fmt(f),
+            (None, Some(usage)) => f.write_str(usage.trim_end()),
+            (None, None) => Ok(()),
+        }
+    }
+}
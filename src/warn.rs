@@ -0,0 +1,122 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::panic::Location;
+
+#[cfg(feature = "json")]
+use json;
+#[cfg(feature = "logfmt")]
+use logfmt;
+use {config, message, output};
+
+/// Prints a warning the way a die message is printed, with the same program prefix, location
+/// and output, only with a `warning: ` label instead of `error: `
+pub(crate) fn print(message: &dyn fmt::Display, location: &Location) {
+    if config::quiet() {
+        return;
+    }
+    let message = message::Message { message, location };
+    output::with(false, |out, terminal| {
+        #[cfg(feature = "json")]
+        if config::json() {
+            let _ = json::write_warning(out, &message);
+            let _ = out.flush();
+            return;
+        }
+        #[cfg(feature = "logfmt")]
+        if config::logfmt() {
+            let _ = logfmt::write_warning(out, &message);
+            let _ = out.flush();
+            return;
+        }
+        let timestamp = message::TimestampPrefix::now();
+        let label = message::warning_label(terminal);
+        let _ = writeln!(out, "{}{}{}", timestamp, label, message);
+        let _ = out.flush();
+    })
+}
+
+/// Extends [`Option<T>`] and [`Result<T, E>`] with methods that print a warning instead of dying,
+/// see [`warn`]!()
+///
+/// [`Option<T>`]: https://doc.rust-lang.org/std/option/enum.Option.html
+/// [`Result<T, E>`]: https://doc.rust-lang.org/std/result/enum.Result.html
+/// [`warn`]: macro.warn.html
+pub trait Warn<T> {
+    /// Converts a [`Result`] or [`Option`] into an [`Option`], printing `msg` to [`stderr`] if it
+    /// was an [`Err`] or [`None`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use die::Warn;
+    /// let port: Option<u16> = "http".parse().ok();
+    /// assert_eq!(port.warn("ignoring invalid port"), None); // prints `ignoring invalid port` to stderr
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [`None`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    fn warn(self, msg: impl fmt::Display) -> Option<T>;
+
+    /// Unwraps a [`Result`] or [`Option`], printing `msg` to [`stderr`] and yielding the default
+    /// value if it was an [`Err`] or [`None`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use die::Warn;
+    /// let jobs: u32 = "many".parse().warn_or_default("ignoring invalid job count"); // prints `ignoring invalid job count` to stderr
+    /// assert_eq!(jobs, 0);
+    /// ```
+    ///
+    /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
+    /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+    /// [`Err`]: https://doc.rust-lang.org/std/result/enum.Result.html#variant.Err
+    /// [`None`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    /// [`stderr`]: https://doc.rust-lang.org/std/io/fn.stderr.html
+    fn warn_or_default(self, msg: impl fmt::Display) -> T
+    where
+        T: Default;
+}
+
+impl<T> Warn<T> for Option<T> {
+    #[inline]
+    #[track_caller]
+    fn warn(self, msg: impl fmt::Display) -> Option<T> {
+        if self.is_none() {
+            print(&msg, Location::caller());
+        }
+        self
+    }
+    #[inline]
+    #[track_caller]
+    fn warn_or_default(self, msg: impl fmt::Display) -> T
+    where
+        T: Default,
+    {
+        self.warn(msg).unwrap_or_default()
+    }
+}
+
+impl<T, E> Warn<T> for Result<T, E> {
+    #[inline]
+    #[track_caller]
+    fn warn(self, msg: impl fmt::Display) -> Option<T> {
+        self.ok().warn(msg)
+    }
+    #[inline]
+    #[track_caller]
+    fn warn_or_default(self, msg: impl fmt::Display) -> T
+    where
+        T: Default,
+    {
+        self.ok().warn_or_default(msg)
+    }
+}
@@ -0,0 +1,139 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe, Location};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use unwind::Died;
+use {exit, DieInfo, IntoExitCode};
+
+struct State {
+    deadline: Instant,
+    canceled: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    condvar: Condvar,
+    timeout: Duration,
+}
+
+/// A handle to the watchdog started by [`after`], to [`feed`] or [`cancel`] it
+///
+/// Dropping it leaves the watchdog running, clones control the same watchdog.
+///
+/// [`after`]: fn.after.html
+/// [`feed`]: #method.feed
+/// [`cancel`]: #method.cancel
+#[derive(Clone)]
+pub struct Watchdog {
+    shared: Arc<Shared>,
+}
+
+impl Watchdog {
+    /// Pushes the deadline back to the full timeout from now
+    pub fn feed(&self) {
+        self.shared.lock().deadline = Instant::now() + self.shared.timeout;
+        self.shared.condvar.notify_one();
+    }
+
+    /// Stops the watchdog, it won't die anymore
+    pub fn cancel(&self) {
+        self.shared.lock().canceled = true;
+        self.shared.condvar.notify_one();
+    }
+}
+
+impl fmt::Debug for Watchdog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("timeout", &self.shared.timeout)
+            .finish()
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> ::std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Starts a watchdog that dies with `msg` and `code` once `timeout` passes without it being fed
+/// or canceled through the returned [`Watchdog`]
+///
+/// A monitor thread waits for the deadline, then dies from there, which exits the whole process,
+/// for a global "never run longer than N minutes" guarantee, even with the `panic` feature or
+/// [`set_no_exit`]. The location reported, like with [`set_location`], is where the watchdog was
+/// started.
+///
+/// [`Watchdog`]: struct.Watchdog.html
+/// [`set_location`]: fn.set_location.html
+/// [`set_no_exit`]: fn.set_no_exit.html
+///
+/// # Examples
+///
+/// ```should_panic
+/// use std::time::Duration;
+///
+/// let watchdog = die::after(Duration::from_millis(50), "took longer than 50ms", 124);
+/// for _ in 0..3 {
+///     std::thread::sleep(Duration::from_millis(10));
+///     watchdog.feed();
+/// }
+/// std::thread::sleep(Duration::from_secs(1)); // prints `took longer than 50ms` then exits with code 124
+/// ```
+#[track_caller]
+pub fn after(timeout: Duration, msg: impl fmt::Display, code: impl IntoExitCode) -> Watchdog {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            deadline: Instant::now() + timeout,
+            canceled: false,
+        }),
+        condvar: Condvar::new(),
+        timeout,
+    });
+    let watchdog = Watchdog {
+        shared: shared.clone(),
+    };
+    let message = msg.to_string();
+    let code = code.into_exit_code();
+    let location = Location::caller();
+    thread::Builder::new()
+        .name("die-watchdog".to_string())
+        .spawn(move || {
+            let mut state = shared.lock();
+            loop {
+                if state.canceled {
+                    return;
+                }
+                let now = Instant::now();
+                if now >= state.deadline {
+                    break;
+                }
+                let wait = state.deadline - now;
+                state = shared
+                    .condvar
+                    .wait_timeout(state, wait)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0;
+            }
+            drop(state);
+            let mut info = DieInfo::new(Some(&message), code);
+            info.location = location;
+            // unwinding would only end this thread, the process must still go, with the code
+            // the die settled on after remapping and clamping
+            let unwound = panic::catch_unwind(AssertUnwindSafe(|| exit(info)));
+            let died = unwound
+                .err()
+                .and_then(|payload| payload.downcast::<Died>().ok());
+            ::std::process::exit(died.map_or(code, |died| died.code()))
+        })
+        .expect("failed to spawn die watchdog thread");
+    watchdog
+}
@@ -0,0 +1,127 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Wrapping of messages printed to a terminal, with the `wrap` feature
+//!
+//! Lines longer than the terminal are broken between words, and continuation lines are indented
+//! to line up after a short leading label like `caused by: ` or `= note: `, or under the message
+//! for its first line. Source excerpts, the `2 | port 8080` lines of [`snippet`], are left alone
+//! so their carets stay under the text they point at.
+//!
+//! [`snippet`]: ../fn.snippet.html
+
+use std::env;
+use std::fmt::{self, Display};
+
+/// Labels longer than this aren't hung from, the text is likely a sentence with a colon in it
+const MAX_LABEL: usize = 16;
+
+/// Wraps `message` if writing to a `terminal`, its first line starts `offset` columns in, after
+/// the `error: ` label
+pub(crate) fn wrap<'a>(message: &'a dyn Display, offset: usize, terminal: bool) -> Wrapped<'a> {
+    if !terminal {
+        return Wrapped::Unwrapped(message);
+    }
+    let text = message.to_string();
+    let width = width();
+    let mut wrapped = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            wrapped.push('\n');
+        }
+        if i > 0 && excerpt(line) {
+            wrapped.push_str(line);
+            continue;
+        }
+        let (offset, hang) = match i {
+            0 => (offset, offset),
+            _ => (0, hang(line)),
+        };
+        wrap_line(&mut wrapped, line, offset, hang, width);
+    }
+    Wrapped::Wrapped(wrapped)
+}
+
+/// A message as [`wrap`] left it, only formatted into a string when it was wrapped
+pub(crate) enum Wrapped<'a> {
+    Unwrapped(&'a dyn Display),
+    Wrapped(String),
+}
+
+impl Display for Wrapped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Wrapped::Unwrapped(message) => message.fmt(f),
+            Wrapped::Wrapped(ref message) => f.write_str(message),
+        }
+    }
+}
+
+/// Whether `line` is a line of a source excerpt, spaces or a line number then a `|` gutter
+fn excerpt(line: &str) -> bool {
+    let rest = line
+        .trim_start_matches(|c: char| c == ' ' || c.is_ascii_digit())
+        .strip_prefix('|');
+    line.starts_with(|c: char| c == ' ' || c.is_ascii_digit())
+        && rest.is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+/// The indentation of continuation lines of `line`: its own, plus a short leading label
+fn hang(line: &str) -> usize {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    match line[indent..].find(": ") {
+        Some(label) if label + 2 <= MAX_LABEL => indent + label + 2,
+        _ => indent,
+    }
+}
+
+fn wrap_line(out: &mut String, line: &str, offset: usize, hang: usize, width: usize) {
+    let mut column = offset;
+    let mut start = true;
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    out.push_str(&line[..indent]);
+    column += indent;
+    for word in line[indent..].split(' ') {
+        let len = word.chars().count();
+        if !start && column + 1 + len > width && len + hang < width {
+            out.push('\n');
+            out.extend((0..hang).map(|_| ' '));
+            column = hang;
+            start = true;
+        }
+        if !start {
+            out.push(' ');
+            column += 1;
+        }
+        out.push_str(word);
+        column += len;
+        start = false;
+    }
+}
+
+/// `COLUMNS` if set, else the width of the terminal on stderr, else 80
+fn width() -> usize {
+    if let Some(columns) = env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) {
+        return columns;
+    }
+    terminal_width().unwrap_or(80)
+}
+
+#[cfg(unix)]
+fn terminal_width() -> Option<usize> {
+    use std::mem;
+    // SAFETY: TIOCGWINSZ only writes a winsize to the pointer it is given
+    let mut size: libc::winsize = unsafe { mem::zeroed() };
+    match unsafe { libc::ioctl(libc::STDERR_FILENO, libc::TIOCGWINSZ, &mut size) } {
+        0 if size.ws_col > 0 => Some(usize::from(size.ws_col)),
+        _ => None,
+    }
+}
+
+#[cfg(not(unix))]
+fn terminal_width() -> Option<usize> {
+    None
+}